@@ -0,0 +1,126 @@
+use crate::{extract, CreateAccount, Error, Mediawiki, Token};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+};
+
+const MAX_UI_ROUNDS: u32 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct AuthManagerInfo {
+    #[serde(default)]
+    pub cancreateaccounts: bool,
+    #[serde(default)]
+    pub requests: Vec<AuthRequest>,
+}
+#[derive(Debug, Deserialize)]
+pub struct AuthRequest {
+    pub id: String,
+    pub required: Option<String>,
+    pub provider: Option<String>,
+    pub account: Option<String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, AuthField>,
+}
+#[derive(Debug, Deserialize)]
+pub struct AuthField {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub sensitive: bool,
+    pub value: Option<String>,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AuthStatus {
+    Pass,
+    Fail,
+    Ui,
+    Redirect,
+    Restart,
+}
+#[derive(Debug, Deserialize)]
+pub struct AuthResponse {
+    pub status: AuthStatus,
+    pub username: Option<String>,
+    pub message: Option<String>,
+    pub messagecode: Option<String>,
+    pub redirecttarget: Option<String>,
+    #[serde(default)]
+    pub requests: Vec<AuthRequest>,
+}
+impl Mediawiki {
+    pub fn authmanager_info(&self, requestsfor: &str) -> Result<AuthManagerInfo, Error> {
+        let mut request = self.request();
+        request.arg("action", "query");
+        request.arg("meta", "authmanagerinfo");
+        request.arg("amirequestsfor", requestsfor);
+        let json = request.get()?;
+        extract(&json["query"], "authmanagerinfo")
+    }
+    pub fn create_account(
+        &self,
+        token: &Token<CreateAccount>,
+        returnurl: Option<&str>,
+        fields: &HashMap<String, String>,
+    ) -> Result<AuthResponse, Error> {
+        let mut request = self.request();
+        request.arg("action", "createaccount");
+        request.arg("createtoken", token.value());
+        match returnurl {
+            Some(returnurl) => request.arg("createreturnurl", returnurl),
+            None => request.arg("createcontinue", "1"),
+        };
+        for (key, val) in fields {
+            request.arg(key, val);
+        }
+        extract(&request.post()?, "createaccount")
+    }
+    pub fn create_account_with<F>(
+        &self,
+        token: &Token<CreateAccount>,
+        returnurl: &str,
+        mut fill: F,
+    ) -> Result<AuthResponse, Error>
+    where
+        F: FnMut(&str, &AuthField) -> Option<String>,
+    {
+        let info = self.authmanager_info("create")?;
+        let mut fields = HashMap::new();
+        for request in &info.requests {
+            for (name, field) in &request.fields {
+                if let Some(val) = fill(name, field) {
+                    fields.insert(name.clone(), val);
+                }
+            }
+        }
+        let mut response = self.create_account(token, Some(returnurl), &fields)?;
+        let mut rounds = 0;
+        while response.status == AuthStatus::Ui {
+            let previous = mem::take(&mut fields);
+            for request in &response.requests {
+                for (name, field) in &request.fields {
+                    if let Some(val) = fill(name, field) {
+                        fields.insert(name.clone(), val);
+                    }
+                }
+            }
+            rounds += 1;
+            if fields.is_empty() || fields == previous || rounds > MAX_UI_ROUNDS {
+                return Err(Error::Invalid(format!(
+                    "Unable to answer account creation prompt: {}",
+                    response.message.as_deref().unwrap_or_default()
+                )));
+            }
+            response = self.create_account(token, None, &fields)?;
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{Mediawiki, QueryBuilder, Title};
+use serde::Deserialize;
+
+#[derive(Clone, Copy)]
+pub enum RedirectFilter {
+    All,
+    Redirects,
+    Nonredirects,
+}
+#[derive(Default)]
+pub struct AllPagesOptions<'a> {
+    pub namespace: Option<i32>,
+    pub prefix: Option<&'a str>,
+    pub filterredir: Option<RedirectFilter>,
+    pub minsize: Option<u64>,
+    pub maxsize: Option<u64>,
+}
+#[derive(Default)]
+pub struct AllImagesOptions<'a> {
+    pub prefix: Option<&'a str>,
+    pub minsize: Option<u64>,
+    pub maxsize: Option<u64>,
+    pub sha1: Option<&'a str>,
+}
+#[derive(Debug, Deserialize)]
+pub struct FileEntry {
+    pub name: String,
+    pub title: String,
+    pub timestamp: Option<String>,
+    pub user: Option<String>,
+    pub comment: Option<String>,
+    pub url: Option<String>,
+    pub descriptionurl: Option<String>,
+    pub size: Option<u64>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub sha1: Option<String>,
+    pub mime: Option<String>,
+}
+#[derive(Debug, Deserialize)]
+pub struct CategoryInfo {
+    pub category: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub pages: u64,
+    #[serde(default)]
+    pub files: u64,
+    #[serde(default)]
+    pub subcats: u64,
+}
+impl Mediawiki {
+    pub fn query_allpages(&self, options: &AllPagesOptions) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("allpages").typed();
+        query.arg("list", "allpages");
+        query.arg("aplimit", "max");
+        query.argo("apnamespace", options.namespace.map(|ns| ns.to_string()));
+        query.argo("apprefix", options.prefix);
+        query.argo(
+            "apfilterredir",
+            options.filterredir.map(|filter| match filter {
+                RedirectFilter::All => "all",
+                RedirectFilter::Redirects => "redirects",
+                RedirectFilter::Nonredirects => "nonredirects",
+            }),
+        );
+        query.argo("apminsize", options.minsize.map(|size| size.to_string()));
+        query.argo("apmaxsize", options.maxsize.map(|size| size.to_string()));
+        query
+    }
+    pub fn query_allredirects(
+        &self,
+        namespace: Option<i32>,
+        prefix: Option<&str>,
+    ) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("allredirects").typed();
+        query.arg("list", "allredirects");
+        query.arg("arlimit", "max");
+        query.arg("arprop", "title");
+        query.flag("arunique", true);
+        query.argo("arnamespace", namespace.map(|ns| ns.to_string()));
+        query.argo("arprefix", prefix);
+        query
+    }
+    pub fn query_allimages(&self, options: &AllImagesOptions) -> QueryBuilder<'_, FileEntry> {
+        let mut query = self.query("allimages").typed();
+        query.arg("list", "allimages");
+        query.arg("ailimit", "max");
+        query.arg("aiprop", "timestamp|user|comment|url|size|sha1|mime");
+        query.argo("aiprefix", options.prefix);
+        query.argo("aiminsize", options.minsize.map(|size| size.to_string()));
+        query.argo("aimaxsize", options.maxsize.map(|size| size.to_string()));
+        query.argo("aisha1", options.sha1);
+        query
+    }
+    pub fn query_allcategories(&self, prefix: Option<&str>) -> QueryBuilder<'_, CategoryInfo> {
+        let mut query = self.query("allcategories").typed();
+        query.arg("list", "allcategories");
+        query.arg("aclimit", "max");
+        query.arg("acprop", "size");
+        query.argo("acprefix", prefix);
+        query
+    }
+    pub fn query_alllinks(
+        &self,
+        namespace: Option<i32>,
+        prefix: Option<&str>,
+    ) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("alllinks").typed();
+        query.arg("list", "alllinks");
+        query.arg("allimit", "max");
+        query.arg("alprop", "title");
+        query.flag("alunique", true);
+        query.argo("alnamespace", namespace.map(|ns| ns.to_string()));
+        query.argo("alprefix", prefix);
+        query
+    }
+}
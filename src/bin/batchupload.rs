@@ -0,0 +1,125 @@
+use mediawiki::{
+    allpages::AllImagesOptions,
+    file::{sha1_file, DownloadOptions},
+    upload::{UploadPolicy, UploadResult},
+    Mediawiki, Upload,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, env::args, fs, path::Path};
+
+#[derive(Clone, Default, Deserialize)]
+struct Description {
+    description: Option<String>,
+    license: Option<String>,
+    categories: Option<Vec<String>>,
+    name: Option<String>,
+}
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    defaults: Description,
+    #[serde(default)]
+    files: HashMap<String, Description>,
+}
+#[derive(PartialEq)]
+enum Conflict {
+    Skip,
+    Overwrite,
+}
+fn page_text(desc: &Description, defaults: &Description) -> String {
+    let description = desc.description.as_ref().or(defaults.description.as_ref());
+    let license = desc.license.as_ref().or(defaults.license.as_ref());
+    let categories = desc.categories.as_ref().or(defaults.categories.as_ref());
+    let mut text = String::new();
+    if let Some(description) = description {
+        text.push_str(&format!("== Summary ==\n{description}\n"));
+    }
+    if let Some(license) = license {
+        text.push_str(&format!("== Licensing ==\n{{{{{license}}}}}\n"));
+    }
+    for category in categories.into_iter().flatten() {
+        text.push_str(&format!("[[Category:{category}]]\n"));
+    }
+    text
+}
+fn upload_dir(dir: &Path, manifest_path: &Path, conflict: Conflict) {
+    let manifest: Manifest =
+        serde_json::from_reader(fs::File::open(manifest_path).unwrap()).unwrap();
+    let manifest_path = manifest_path.canonicalize().unwrap();
+    let mw = Mediawiki::login_path("ftb.json").unwrap();
+    let token = mw.get_token().unwrap();
+    let policy = UploadPolicy {
+        exists: conflict == Conflict::Overwrite,
+        was_deleted: true,
+        ..UploadPolicy::default()
+    };
+    let (mut uploaded, mut identical, mut conflicts, mut failed) = (0, 0, 0, 0);
+    let mut entries = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.canonicalize().ok().as_ref() != Some(&manifest_path))
+        .collect::<Vec<_>>();
+    entries.sort();
+    for path in entries {
+        let filename = path.file_name().unwrap().to_string_lossy().into_owned();
+        let desc = manifest.files.get(&filename).cloned().unwrap_or_default();
+        let name = desc.name.clone().unwrap_or_else(|| filename.clone());
+        let sha1 = sha1_file(&path).unwrap();
+        let options = AllImagesOptions {
+            sha1: Some(&sha1),
+            ..AllImagesOptions::default()
+        };
+        let existing = mw
+            .query_allimages(&options)
+            .into_iter()
+            .map(|file| file.unwrap().name)
+            .collect::<Vec<_>>();
+        if !existing.is_empty() {
+            println!("Identical: {name} ({})", existing.join(", "));
+            identical += 1;
+            continue;
+        }
+        let info = mw.image_info(&name, &DownloadOptions::default()).unwrap();
+        if info.is_some() && conflict == Conflict::Skip {
+            println!("Conflict: {name}");
+            conflicts += 1;
+            continue;
+        }
+        let text = page_text(&desc, &manifest.defaults);
+        match mw.upload_with_policy(
+            &name,
+            &token,
+            Upload::File(&path),
+            Some(&text),
+            Some("Batch upload"),
+            &policy,
+        ) {
+            Ok(UploadResult::Success { filename, .. }) => {
+                println!("Uploaded: {filename}");
+                uploaded += 1;
+            }
+            Ok(other) => {
+                println!("Failed: {name} {other:?}");
+                failed += 1;
+            }
+            Err(e) => {
+                println!("Failed: {name} {e:?}");
+                failed += 1;
+            }
+        }
+    }
+    println!(
+        "{uploaded} uploaded, {identical} already on wiki, {conflicts} conflicts skipped, {failed} failed"
+    );
+}
+fn main() {
+    let dir = args().nth(1).unwrap();
+    let manifest = args().nth(2).unwrap_or_else(|| "manifest.json".into());
+    let conflict = match args().nth(3).as_deref() {
+        None | Some("--on-conflict=skip") => Conflict::Skip,
+        Some("--on-conflict=overwrite") => Conflict::Overwrite,
+        Some(other) => panic!("Unknown option: {other}"),
+    };
+    upload_dir(Path::new(&dir), Path::new(&manifest), conflict);
+}
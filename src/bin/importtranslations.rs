@@ -0,0 +1,43 @@
+use mediawiki::{
+    tilesheet::{parse_json_lang, parse_lang, Tilesheet},
+    Mediawiki,
+};
+use std::{collections::BTreeMap, env::args, fs};
+
+fn load(path: &str) -> BTreeMap<String, String> {
+    let text = fs::read_to_string(path).unwrap();
+    if path.ends_with(".json") {
+        parse_json_lang(&text).unwrap()
+    } else {
+        parse_lang(&text)
+    }
+}
+fn import(mod_name: &str, english: &str, localized: &str, lang: &str) {
+    let mw = Mediawiki::login_path("ftb.json").unwrap();
+    let token = mw.get_token().unwrap();
+    let report = mw
+        .import_translations(
+            &token,
+            Some(mod_name),
+            &load(english),
+            &load(localized),
+            lang,
+        )
+        .unwrap();
+    for name in &report.unmatched {
+        println!("No tile named {name}");
+    }
+    for (id, e) in &report.failed {
+        println!("Failed to translate tile {id}: {e:?}");
+    }
+    println!(
+        "{} tiles translated, {} names unmatched, {} failed",
+        report.translated.len(),
+        report.unmatched.len(),
+        report.failed.len()
+    );
+}
+fn main() {
+    let args = args().collect::<Vec<_>>();
+    import(&args[1], &args[2], &args[3], &args[4]);
+}
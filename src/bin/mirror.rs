@@ -0,0 +1,154 @@
+use mediawiki::{
+    allpages::AllImagesOptions,
+    file::{sha1_file, DownloadOptions, ImageInfo},
+    links::{CategoryMemberType, CategorySort},
+    tilesheet::Tilesheet,
+    Mediawiki,
+};
+use serde::Serialize;
+use std::{
+    env::args,
+    fs::{create_dir_all, remove_file, rename, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize)]
+struct Record {
+    name: String,
+    sha1: Option<String>,
+    size: Option<u64>,
+    mime: Option<String>,
+    user: Option<String>,
+    timestamp: Option<String>,
+    upload_comment: Option<String>,
+    description: Option<String>,
+}
+impl Record {
+    fn from_info(name: String, info: ImageInfo) -> Record {
+        Record {
+            name,
+            sha1: info.sha1,
+            size: info.size,
+            mime: info.mime,
+            user: info.user,
+            timestamp: info.timestamp,
+            upload_comment: info.comment,
+            description: None,
+        }
+    }
+}
+fn all_images(mw: &Mediawiki) -> Vec<Record> {
+    mw.query_allimages(&AllImagesOptions::default())
+        .into_iter()
+        .map(|file| {
+            let file = file.unwrap();
+            Record {
+                name: file.name,
+                sha1: file.sha1,
+                size: file.size,
+                mime: file.mime,
+                user: file.user,
+                timestamp: file.timestamp,
+                upload_comment: file.comment,
+                description: None,
+            }
+        })
+        .collect()
+}
+fn lookup(mw: &Mediawiki, names: Vec<String>) -> Vec<Record> {
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let info = mw.image_info(&name, &DownloadOptions::default()).unwrap();
+            if info.is_none() {
+                println!("Missing: {name}");
+            }
+            info.map(|info| Record::from_info(name, info))
+        })
+        .collect()
+}
+fn category_images(mw: &Mediawiki, category: &str) -> Vec<Record> {
+    let names = mw
+        .query_categorymembers(
+            &format!("Category:{category}"),
+            &[CategoryMemberType::File],
+            CategorySort::Sortkey,
+            &[],
+        )
+        .into_iter()
+        .map(|title| {
+            let title = title.unwrap().title;
+            title
+                .split_once(':')
+                .map_or(title.clone(), |(_, name)| name.into())
+        })
+        .collect();
+    lookup(mw, names)
+}
+fn tilesheet_images(mw: &Mediawiki) -> Vec<Record> {
+    let names = mw
+        .query_sheets_typed()
+        .into_iter()
+        .flat_map(|sheet| {
+            let sheet = sheet.unwrap();
+            let name = sheet.mod_name;
+            sheet
+                .sizes
+                .into_iter()
+                .map(move |size| format!("Tilesheet {name} {size}.png"))
+        })
+        .collect();
+    lookup(mw, names)
+}
+fn sidecar_path(dir: &Path, record: &Record) -> PathBuf {
+    dir.join(format!("{}.json", record.name))
+}
+fn write_sidecar(mw: &Mediawiki, dir: &Path, record: &mut Record) {
+    record.description = mw.page_content(&format!("File:{}", record.name)).unwrap();
+    let sidecar = File::create(sidecar_path(dir, record)).unwrap();
+    serde_json::to_writer_pretty(sidecar, &*record).unwrap();
+}
+fn mirror(dir: &Path, source: Option<&str>, arg: Option<&str>) {
+    let mw = Mediawiki::login_path("ftb.json").unwrap();
+    create_dir_all(dir).unwrap();
+    let records = match source {
+        None => all_images(&mw),
+        Some("--category") => category_images(&mw, arg.unwrap()),
+        Some("--tilesheets") => tilesheet_images(&mw),
+        Some(other) => panic!("Unknown source: {other}"),
+    };
+    let (mut skipped, mut downloaded, mut failed) = (0, 0, 0);
+    for mut record in records {
+        let path = dir.join(&record.name);
+        if path.exists() && record.sha1.is_some() && sha1_file(&path).ok() == record.sha1 {
+            if !sidecar_path(dir, &record).exists() {
+                write_sidecar(&mw, dir, &mut record);
+            }
+            skipped += 1;
+            continue;
+        }
+        let partial = dir.join(format!("{}.part", record.name));
+        let file = BufWriter::new(File::create(&partial).unwrap());
+        match mw.download_file_to(&record.name, &DownloadOptions::default(), file) {
+            Ok(_) => {
+                rename(&partial, &path).unwrap();
+                write_sidecar(&mw, dir, &mut record);
+                println!("Downloaded: {}", record.name);
+                downloaded += 1;
+            }
+            Err(e) => {
+                let _ = remove_file(&partial);
+                println!("Failed: {} {e:?}", record.name);
+                failed += 1;
+            }
+        }
+    }
+    println!("{downloaded} downloaded, {skipped} up to date, {failed} failed");
+}
+fn main() {
+    let dir = args().nth(1).unwrap_or_else(|| "mirror".into());
+    let source = args().nth(2);
+    let arg = args().nth(3);
+    mirror(Path::new(&dir), source.as_deref(), arg.as_deref());
+}
@@ -1,16 +1,37 @@
 use mediawiki::{tilesheet::Tilesheet, Mediawiki};
 use std::env::args;
 
-fn purge(mod_name: &str) {
+fn report_usage(mw: &Mediawiki, mod_name: &str) -> usize {
+    let mut uses = 0;
+    for sheet in mw.query_sheets_typed() {
+        let sheet = sheet.unwrap();
+        if sheet.mod_name != mod_name {
+            continue;
+        }
+        for size in sheet.sizes {
+            let file = format!("File:Tilesheet {mod_name} {size}.png");
+            for page in mw.query_imageusage(&file, &[]) {
+                let page = page.unwrap();
+                println!("{file} is still used by {}", page.title);
+                uses += 1;
+            }
+        }
+    }
+    uses
+}
+fn purge(mod_name: &str, force: bool) {
     let mw = Mediawiki::login_path("ftb.json").unwrap();
+    let uses = report_usage(&mw, mod_name);
+    if uses > 0 && !force {
+        println!(
+            "Tilesheet {mod_name} is still used on {uses} pages, pass --force to purge anyway"
+        );
+        return;
+    }
     let todelete: Vec<String> = mw
-        .query_tiles(Some(mod_name))
+        .query_tiles_typed(Some(mod_name))
         .into_iter()
-        .map(|tile| {
-            let tile = tile.unwrap();
-            let tile = tile.as_object().unwrap();
-            tile["id"].as_i64().unwrap().to_string()
-        })
+        .map(|tile| tile.unwrap().id.to_string())
         .collect();
     let token = mw.get_token().unwrap();
     for chunk in todelete.chunks(100) {
@@ -23,5 +44,6 @@ fn purge(mod_name: &str) {
 }
 fn main() {
     let name = args().nth(1).unwrap();
-    purge(&name);
+    let force = args().skip(2).any(|arg| arg == "--force");
+    purge(&name, force);
 }
@@ -1,12 +1,33 @@
-use mediawiki::{Error, Mediawiki};
+use mediawiki::{compare::diff_lines, Error, Mediawiki};
 use std::collections::HashMap;
+use std::env::args;
 use std::fs::{create_dir, File};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
+fn write_diff(mw: &Mediawiki, base: &Path, old_revid: u64, revid: u64) -> Result<(), Error> {
+    let (Some(old), Some(new)) = (mw.revision_content(old_revid)?, mw.revision_content(revid)?)
+    else {
+        println!("Skipping diff {old_revid}..{revid}: revision content unavailable");
+        return Ok(());
+    };
+    let mut file = BufWriter::new(File::create(
+        base.join(revid.to_string()).with_extension("diff"),
+    )?);
+    writeln!(&mut file, "--- {old_revid}")?;
+    writeln!(&mut file, "+++ {revid}")?;
+    for line in diff_lines(&old, &new) {
+        writeln!(&mut file, "{line}")?;
+    }
+    Ok(())
+}
 fn main() {
+    let diffs = args().any(|arg| arg == "--diffs");
     let base = Path::new("rc");
     let _ = create_dir(base);
+    if diffs {
+        let _ = create_dir(base.join("diffs"));
+    }
     let mut files: HashMap<PathBuf, BufWriter<File>> = HashMap::new();
     let mw = Mediawiki::login_path("ftb.json").unwrap();
     for change in mw.query_recentchanges(5000) {
@@ -31,6 +52,13 @@ fn main() {
                 .entry(name.clone())
                 .or_insert_with(|| BufWriter::new(File::create(name).unwrap()));
             writeln!(&mut file, "{change}")?;
+            if diffs && kind == "edit" {
+                let revid = change["revid"].as_u64().unwrap_or_default();
+                let old_revid = change["old_revid"].as_u64().unwrap_or_default();
+                if revid != 0 && old_revid != 0 {
+                    write_diff(&mw, &base.join("diffs"), old_revid, revid)?;
+                }
+            }
             Ok(())
         })() {
             println!("Error: {e:?}");
@@ -0,0 +1,34 @@
+use mediawiki::Mediawiki;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env::args,
+    fs::File,
+};
+
+#[derive(Deserialize)]
+struct Roster {
+    managed: Vec<String>,
+    users: BTreeMap<String, BTreeSet<String>>,
+}
+fn sync(path: &str) {
+    let roster: Roster = serde_json::from_reader(File::open(path).unwrap()).unwrap();
+    let mw = Mediawiki::login_path("ftb.json").unwrap();
+    let token = mw.get_token().unwrap();
+    let managed = roster.managed.iter().map(|g| &**g).collect::<Vec<_>>();
+    let results = mw
+        .ensure_groups(
+            &token,
+            &roster.users,
+            &managed,
+            Some("Syncing staff roster"),
+        )
+        .unwrap();
+    for result in results {
+        println!("{}: +{:?} -{:?}", result.user, result.added, result.removed);
+    }
+}
+fn main() {
+    let path = args().nth(1).unwrap_or_else(|| "roster.json".into());
+    sync(&path);
+}
@@ -10,14 +10,11 @@ fn import() {
     let mw = Mediawiki::login_path("ftb.json").unwrap();
     let mut file = BufWriter::new(File::create(path).unwrap());
     let tiles: HashSet<(String, String)> = mw
-        .query_tiles(None)
+        .query_tiles_typed(None)
         .into_iter()
         .map(|tile| {
             let tile = tile.unwrap();
-            let tile = tile.as_object().unwrap();
-            let name = tile["name"].as_str().unwrap();
-            let modd = tile["mod"].as_str().unwrap();
-            (name.into(), modd.into())
+            (tile.name, tile.mod_name)
         })
         .collect();
     // let mut todelete: Vec<String> = Vec::new();
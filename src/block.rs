@@ -0,0 +1,167 @@
+use crate::{extract, Csrf, Error, Json, Mediawiki, QueryBuilder, Title, Token};
+use serde::{Deserialize, Deserializer};
+
+#[derive(Default)]
+pub struct BlockOptions<'a> {
+    pub expiry: Option<&'a str>,
+    pub reason: Option<&'a str>,
+    pub pages: &'a [&'a str],
+    pub namespaces: &'a [i32],
+    pub anononly: bool,
+    pub nocreate: bool,
+    pub autoblock: bool,
+    pub noemail: bool,
+    pub hidename: bool,
+    pub allowusertalk: bool,
+    pub reblock: bool,
+    pub watchuser: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct BlockResult {
+    pub user: String,
+    #[serde(rename = "userID")]
+    pub userid: u64,
+    pub id: u64,
+    pub expiry: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub anononly: bool,
+    #[serde(default)]
+    pub nocreate: bool,
+    #[serde(default)]
+    pub autoblock: bool,
+    #[serde(default)]
+    pub noemail: bool,
+    #[serde(default)]
+    pub hidename: bool,
+    #[serde(default)]
+    pub allowusertalk: bool,
+    #[serde(default)]
+    pub partial: bool,
+    #[serde(default)]
+    pub pagerestrictions: Vec<String>,
+    #[serde(default)]
+    pub namespacerestrictions: Vec<i32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct UnblockResult {
+    pub id: u64,
+    pub user: String,
+    pub userid: u64,
+    #[serde(default)]
+    pub reason: String,
+}
+#[derive(Debug, Deserialize)]
+pub struct Block {
+    pub id: u64,
+    pub user: Option<String>,
+    pub userid: Option<u64>,
+    #[serde(default)]
+    pub by: String,
+    #[serde(default)]
+    pub byid: u64,
+    pub timestamp: String,
+    pub expiry: String,
+    #[serde(default)]
+    pub reason: String,
+    pub rangestart: Option<String>,
+    pub rangeend: Option<String>,
+    #[serde(default)]
+    pub automatic: bool,
+    #[serde(default)]
+    pub anononly: bool,
+    #[serde(default)]
+    pub nocreate: bool,
+    #[serde(default)]
+    pub autoblock: bool,
+    #[serde(default)]
+    pub noemail: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub allowusertalk: bool,
+    #[serde(default)]
+    pub partial: bool,
+    #[serde(default, deserialize_with = "restrictions")]
+    pub restrictions: BlockRestrictions,
+}
+#[derive(Debug, Default, Deserialize)]
+pub struct BlockRestrictions {
+    #[serde(default)]
+    pub pages: Vec<Title>,
+    #[serde(default)]
+    pub namespaces: Vec<i32>,
+}
+fn restrictions<'de, D>(deserializer: D) -> Result<BlockRestrictions, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Json::deserialize(deserializer)? {
+        Json::Object(map) => {
+            BlockRestrictions::deserialize(Json::Object(map)).map_err(serde::de::Error::custom)
+        }
+        _ => Ok(BlockRestrictions::default()),
+    }
+}
+impl Mediawiki {
+    pub fn block(
+        &self,
+        token: &Token<Csrf>,
+        user: &str,
+        options: &BlockOptions,
+    ) -> Result<BlockResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "block");
+        request.arg("token", token.value());
+        request.arg("user", user);
+        request.argo("expiry", options.expiry);
+        request.argo("reason", options.reason);
+        if !options.pages.is_empty() || !options.namespaces.is_empty() {
+            request.flag("partial", true);
+        }
+        if !options.pages.is_empty() {
+            request.arg("pagerestrictions", options.pages.join("|"));
+        }
+        if !options.namespaces.is_empty() {
+            let namespaces = options
+                .namespaces
+                .iter()
+                .map(|ns| ns.to_string())
+                .collect::<Vec<_>>();
+            request.arg("namespacerestrictions", namespaces.join("|"));
+        }
+        request.flag("anononly", options.anononly);
+        request.flag("nocreate", options.nocreate);
+        request.flag("autoblock", options.autoblock);
+        request.flag("noemail", options.noemail);
+        request.flag("hidename", options.hidename);
+        request.flag("allowusertalk", options.allowusertalk);
+        request.flag("reblock", options.reblock);
+        request.flag("watchuser", options.watchuser);
+        extract(&request.post()?, "block")
+    }
+    pub fn unblock(
+        &self,
+        token: &Token<Csrf>,
+        user: &str,
+        reason: Option<&str>,
+    ) -> Result<UnblockResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "unblock");
+        request.arg("token", token.value());
+        request.arg("user", user);
+        request.argo("reason", reason);
+        extract(&request.post()?, "unblock")
+    }
+    pub fn query_blocks(&self) -> QueryBuilder<'_, Block> {
+        let mut query = self.query("blocks").typed();
+        query.arg("list", "blocks");
+        query.arg("bklimit", "max");
+        query.arg(
+            "bkprop",
+            "id|user|userid|by|byid|timestamp|expiry|reason|range|flags|restrictions",
+        );
+        query
+    }
+}
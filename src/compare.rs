@@ -0,0 +1,183 @@
+use crate::{extract, Error, Mediawiki, RequestBuilder};
+use serde::Deserialize;
+use std::fmt;
+
+const MAX_LCS_CELLS: usize = 1 << 22;
+
+pub enum CompareSide<'a> {
+    Rev(u64),
+    Title(&'a str),
+    Text(&'a str),
+}
+impl<'a> CompareSide<'a> {
+    fn apply(&self, request: &mut RequestBuilder, side: &str) {
+        match *self {
+            CompareSide::Rev(revid) => request.arg(format!("{side}rev"), revid.to_string()),
+            CompareSide::Title(title) => request.arg(format!("{side}title"), title),
+            CompareSide::Text(text) => request
+                .arg(format!("{side}slots"), "main")
+                .arg(format!("{side}text-main"), text)
+                .arg(format!("{side}contentmodel-main"), "wikitext"),
+        };
+    }
+}
+#[derive(Debug, Deserialize)]
+pub struct CompareResult {
+    pub fromid: Option<u64>,
+    pub fromrevid: Option<u64>,
+    pub fromns: Option<i32>,
+    pub fromtitle: Option<String>,
+    pub toid: Option<u64>,
+    pub torevid: Option<u64>,
+    pub tons: Option<i32>,
+    pub totitle: Option<String>,
+    #[serde(default)]
+    pub body: String,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+impl<'a> fmt::Display for DiffLine<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffLine::Same(line) => write!(f, " {line}"),
+            DiffLine::Added(line) => write!(f, "+{line}"),
+            DiffLine::Removed(line) => write!(f, "-{line}"),
+        }
+    }
+}
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let mut diff = old[..prefix]
+        .iter()
+        .map(|line| DiffLine::Same(line))
+        .collect::<Vec<_>>();
+    if a.len().saturating_mul(b.len()) > MAX_LCS_CELLS {
+        diff.extend(a.iter().map(|line| DiffLine::Removed(line)));
+        diff.extend(b.iter().map(|line| DiffLine::Added(line)));
+        diff.extend(
+            old[old.len() - suffix..]
+                .iter()
+                .map(|line| DiffLine::Same(line)),
+        );
+        return diff;
+    }
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(DiffLine::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(a[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(b[j..].iter().map(|line| DiffLine::Added(line)));
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| DiffLine::Same(line)),
+    );
+    diff
+}
+impl Mediawiki {
+    pub fn compare(&self, from: CompareSide, to: CompareSide) -> Result<CompareResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "compare");
+        request.arg("prop", "diff|ids|title");
+        from.apply(&mut request, "from");
+        to.apply(&mut request, "to");
+        extract(&request.post()?, "compare")
+    }
+    pub fn revision_content(&self, revid: u64) -> Result<Option<String>, Error> {
+        let mut request = self.request();
+        request.arg("action", "query");
+        request.arg("prop", "revisions");
+        request.arg("revids", revid.to_string());
+        request.arg("rvprop", "content");
+        request.arg("rvslots", "main");
+        let json = request.get()?;
+        let page = &json["query"]["pages"][0];
+        if page.is_null() || !page["missing"].is_null() {
+            return Ok(None);
+        }
+        Ok(page["revisions"][0]["slots"]["main"]["content"]
+            .as_str()
+            .map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_lines, DiffLine::*};
+
+    #[test]
+    fn identical() {
+        assert_eq!(diff_lines("a\nb", "a\nb"), vec![Same("a"), Same("b")]);
+    }
+    #[test]
+    fn insert_only() {
+        assert_eq!(
+            diff_lines("a\nc", "a\nb\nc\nd"),
+            vec![Same("a"), Added("b"), Same("c"), Added("d")]
+        );
+    }
+    #[test]
+    fn delete_only() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "b\nd"),
+            vec![Removed("a"), Same("b"), Removed("c"), Same("d")]
+        );
+    }
+    #[test]
+    fn replaced_middle() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nx\ny\nd"),
+            vec![
+                Same("a"),
+                Removed("b"),
+                Removed("c"),
+                Added("x"),
+                Added("y"),
+                Same("d")
+            ]
+        );
+    }
+    #[test]
+    fn oversized_falls_back_to_replacement() {
+        let old = (0..3000).map(|i| format!("o{i}\n")).collect::<String>();
+        let new = (0..3000).map(|i| format!("n{i}\n")).collect::<String>();
+        let diff = diff_lines(&old, &new);
+        assert_eq!(diff.len(), 6000);
+        assert_eq!(diff[0], Removed("o0"));
+        assert_eq!(diff[3000], Added("n0"));
+    }
+}
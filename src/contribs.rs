@@ -0,0 +1,99 @@
+use crate::{
+    join_namespaces,
+    moderation::{EditResult, RollbackResult},
+    Csrf, Error, Mediawiki, QueryBuilder, Rollback, Token,
+};
+use serde::Deserialize;
+
+pub enum ContribTarget<'a> {
+    Users(&'a [&'a str]),
+    Prefix(&'a str),
+    IpRange(&'a str),
+}
+#[derive(Default)]
+pub struct ContribsOptions<'a> {
+    pub namespaces: &'a [i32],
+    pub start: Option<&'a str>,
+    pub end: Option<&'a str>,
+    pub show: &'a [&'a str],
+}
+#[derive(Debug, Deserialize)]
+pub struct Contribution {
+    #[serde(default)]
+    pub userid: u64,
+    pub user: String,
+    pub pageid: u64,
+    pub revid: u64,
+    #[serde(default)]
+    pub parentid: u64,
+    pub ns: i32,
+    pub title: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub new: bool,
+    #[serde(default)]
+    pub minor: bool,
+    #[serde(default)]
+    pub top: bool,
+    #[serde(default)]
+    pub comment: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub sizediff: i64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+impl Mediawiki {
+    pub fn query_usercontribs(
+        &self,
+        target: ContribTarget,
+        options: &ContribsOptions,
+    ) -> QueryBuilder<'_, Contribution> {
+        let mut query = self.query("usercontribs").typed();
+        query.arg("list", "usercontribs");
+        query.arg("uclimit", "max");
+        query.arg(
+            "ucprop",
+            "ids|title|timestamp|comment|size|sizediff|flags|tags",
+        );
+        match target {
+            ContribTarget::Users(users) => query.arg("ucuser", users.join("|")),
+            ContribTarget::Prefix(prefix) => query.arg("ucuserprefix", prefix),
+            ContribTarget::IpRange(range) => query.arg("uciprange", range),
+        };
+        query.argo("ucnamespace", join_namespaces(options.namespaces));
+        query.argo("ucstart", options.start);
+        query.argo("ucend", options.end);
+        if !options.show.is_empty() {
+            query.arg("ucshow", options.show.join("|"));
+        }
+        query
+    }
+    pub fn rollback_contribution(
+        &self,
+        token: &Token<Rollback>,
+        contribution: &Contribution,
+        summary: Option<&str>,
+    ) -> Result<Option<RollbackResult>, Error> {
+        if !contribution.top {
+            return Ok(None);
+        }
+        self.rollback(token, &contribution.title, &contribution.user, summary)
+            .map(Some)
+    }
+    pub fn undo_contribution(
+        &self,
+        token: &Token<Csrf>,
+        contribution: &Contribution,
+        summary: Option<&str>,
+    ) -> Result<EditResult, Error> {
+        self.undo(
+            token,
+            &contribution.title,
+            contribution.revid,
+            None,
+            summary,
+        )
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{Error, Json, Mediawiki, QueryBuilder};
+use reqwest::{header::USER_AGENT, Method, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use sha1::{Digest, Sha1};
+use std::{
+    fs::File,
+    io::{self, Result as IoResult, Write},
+    path::Path,
+    thread::sleep,
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub enum DownloadError {
+    Missing,
+    Status(StatusCode),
+    Sha1Mismatch { expected: String, actual: String },
+    SizeMismatch { expected: u64, actual: u64 },
+}
+#[derive(Debug, Deserialize)]
+pub struct ImageInfo {
+    pub timestamp: Option<String>,
+    pub user: Option<String>,
+    pub comment: Option<String>,
+    pub url: Option<String>,
+    pub descriptionurl: Option<String>,
+    pub thumburl: Option<String>,
+    pub size: Option<u64>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub sha1: Option<String>,
+    pub mime: Option<String>,
+    pub archivename: Option<String>,
+    #[serde(default)]
+    pub metadata: Json,
+}
+#[derive(Debug, Deserialize)]
+pub struct DuplicateFile {
+    pub name: String,
+    pub user: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub shared: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct ArchivedFile {
+    pub id: u64,
+    pub name: String,
+    pub ns: i32,
+    pub title: String,
+    pub timestamp: Option<String>,
+    pub user: Option<String>,
+    pub description: Option<String>,
+    pub size: Option<u64>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub sha1: Option<String>,
+    pub mime: Option<String>,
+    pub archivename: Option<String>,
+    #[serde(default)]
+    pub metadata: Json,
+    #[serde(default)]
+    pub filehidden: bool,
+}
+pub struct DownloadOptions<'a> {
+    pub timestamp: Option<&'a str>,
+    pub width: Option<u32>,
+    pub retries: u32,
+}
+impl<'a> Default for DownloadOptions<'a> {
+    fn default() -> DownloadOptions<'a> {
+        DownloadOptions {
+            timestamp: None,
+            width: None,
+            retries: 3,
+        }
+    }
+}
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha1,
+    len: u64,
+}
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+        self.len += len as u64;
+        Ok(len)
+    }
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+impl Mediawiki {
+    pub fn image_info(
+        &self,
+        name: &str,
+        options: &DownloadOptions,
+    ) -> Result<Option<ImageInfo>, Error> {
+        let mut request = self.request();
+        request.arg("action", "query");
+        request.arg("prop", "imageinfo");
+        request.arg("titles", format!("File:{name}"));
+        request.arg(
+            "iiprop",
+            "timestamp|user|comment|url|size|sha1|mime|archivename",
+        );
+        if let Some(timestamp) = options.timestamp {
+            request.arg("iistart", timestamp);
+            request.arg("iiend", timestamp);
+        }
+        request.argo("iiurlwidth", options.width.map(|width| width.to_string()));
+        let json = request.get_bounded(options.retries)?;
+        let image = &json["query"]["pages"][0];
+        if image.is_null() {
+            return Err(Error::Json(json.clone()));
+        }
+        match &image["imageinfo"][0] {
+            Json::Null => Ok(None),
+            info => ImageInfo::deserialize(info)
+                .map(Some)
+                .map_err(|_| Error::Json(json.clone())),
+        }
+    }
+    fn file_prop<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        prop: &str,
+        args: &[(&str, &str)],
+    ) -> Result<Vec<T>, Error> {
+        let mut query = self.query("pages");
+        query.arg("prop", prop);
+        query.arg("titles", format!("File:{name}"));
+        for &(key, val) in args {
+            query.arg(key, val);
+        }
+        let mut items = Vec::new();
+        for page in query {
+            let page = page?;
+            for item in page[prop].as_array().into_iter().flatten() {
+                items.push(T::deserialize(item).map_err(|_| Error::Json(page.clone()))?);
+            }
+        }
+        Ok(items)
+    }
+    pub fn file_history(&self, name: &str) -> Result<Vec<ImageInfo>, Error> {
+        self.file_prop(
+            name,
+            "imageinfo",
+            &[
+                ("iilimit", "max"),
+                (
+                    "iiprop",
+                    "timestamp|user|comment|url|size|dimensions|sha1|mime|metadata|archivename",
+                ),
+            ],
+        )
+    }
+    pub fn duplicate_files(&self, name: &str) -> Result<Vec<DuplicateFile>, Error> {
+        self.file_prop(name, "duplicatefiles", &[("dflimit", "max")])
+    }
+    pub fn query_filearchive(&self, prefix: Option<&str>) -> QueryBuilder<'_, ArchivedFile> {
+        let mut query = self.query("filearchive").typed();
+        query.arg("list", "filearchive");
+        query.arg("falimit", "max");
+        query.arg(
+            "faprop",
+            "sha1|timestamp|user|size|dimensions|description|mime|metadata|archivename",
+        );
+        query.argo("faprefix", prefix);
+        query
+    }
+    pub fn download_file_to<W: Write>(
+        &self,
+        name: &str,
+        options: &DownloadOptions,
+        writer: W,
+    ) -> Result<ImageInfo, Error> {
+        let info = self
+            .image_info(name, options)?
+            .ok_or(DownloadError::Missing)?;
+        let url = match options.width {
+            Some(_) => info.thumburl.as_deref(),
+            None => info.url.as_deref(),
+        }
+        .ok_or(DownloadError::Missing)?;
+        let mut attempt = 0;
+        let mut response = loop {
+            let request = self
+                .client
+                .request(Method::GET, url)
+                .header(USER_AGENT, &*self.config.useragent);
+            let retry = match request.send() {
+                Ok(response) if response.status() == StatusCode::OK => break response,
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+                        return Err(DownloadError::Status(status).into());
+                    }
+                    Error::Download(DownloadError::Status(status))
+                }
+                Err(err) => err.into(),
+            };
+            if attempt >= options.retries {
+                return Err(retry);
+            }
+            println!("{retry:?}");
+            attempt += 1;
+            sleep(Duration::from_secs(5));
+        };
+        let mut writer = HashingWriter {
+            inner: writer,
+            hasher: Sha1::new(),
+            len: 0,
+        };
+        response.copy_to(&mut writer)?;
+        writer.flush()?;
+        if options.width.is_some() {
+            return Ok(info);
+        }
+        if let Some(expected) = info.size {
+            if expected != writer.len {
+                return Err(DownloadError::SizeMismatch {
+                    expected,
+                    actual: writer.len,
+                }
+                .into());
+            }
+        }
+        if let Some(expected) = &info.sha1 {
+            let actual = format!("{:x}", writer.hasher.finalize());
+            if *expected != actual {
+                return Err(DownloadError::Sha1Mismatch {
+                    expected: expected.clone(),
+                    actual,
+                }
+                .into());
+            }
+        }
+        Ok(info)
+    }
+    pub fn download_file(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        let mut buf = Vec::new();
+        match self.download_file_to(name, &DownloadOptions::default(), &mut buf) {
+            Ok(_) => Ok(Some(buf)),
+            Err(Error::Download(DownloadError::Missing)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+pub fn sha1_file(path: &Path) -> Result<String, Error> {
+    let mut hasher = Sha1::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
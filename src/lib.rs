@@ -1,27 +1,41 @@
+use crate::{file::DownloadError, tilesheet::RenameError};
 use cookie::{Cookie, CookieJar, ParseError as CookieError};
 use reqwest::{
     blocking::{multipart::Form, Client},
     header::{COOKIE, SET_COOKIE, USER_AGENT},
-    Error as ReqwestError, Method, StatusCode,
+    Error as ReqwestError, Method,
 };
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::{Error as ParseError, Value as Json};
 use std::{
-    cell::RefCell,
-    collections::HashMap,
-    fs::File,
-    io::{Error as IoError, Read},
-    marker::PhantomData,
-    path::Path,
-    thread::sleep,
-    time::Duration,
+    cell::RefCell, collections::HashMap, fs::File, io::Error as IoError, marker::PhantomData,
+    path::Path, thread::sleep, time::Duration,
 };
 
+pub mod account;
+pub mod allpages;
+pub mod block;
+pub mod compare;
+pub mod contribs;
+pub mod file;
+pub mod links;
+pub mod logevents;
+pub mod moderation;
 pub mod oredict;
+pub mod page;
+pub mod parse;
+pub mod search;
 pub mod tilesheet;
+pub mod upload;
+pub mod users;
+pub mod watchlist;
 
 #[derive(Debug)]
 pub enum Error {
+    Api(ApiError),
+    Download(DownloadError),
+    Rename(Box<RenameError>),
+    Invalid(String),
     Json(Json),
     Io(IoError),
     Parse(ParseError),
@@ -29,6 +43,21 @@ pub enum Error {
     Reqwest(ReqwestError),
     Status(String),
 }
+impl From<ApiError> for Error {
+    fn from(err: ApiError) -> Error {
+        Error::Api(err)
+    }
+}
+impl From<DownloadError> for Error {
+    fn from(err: DownloadError) -> Error {
+        Error::Download(err)
+    }
+}
+impl From<RenameError> for Error {
+    fn from(err: RenameError) -> Error {
+        Error::Rename(Box::new(err))
+    }
+}
 impl From<Json> for Error {
     fn from(err: Json) -> Error {
         Error::Json(err)
@@ -60,6 +89,75 @@ impl From<String> for Error {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ApiError {
+    pub code: String,
+    #[serde(default)]
+    pub info: String,
+    pub offset: Option<u64>,
+}
+
+fn extract<T: DeserializeOwned>(json: &Json, key: &str) -> Result<T, Error> {
+    T::deserialize(&json[key]).map_err(|_| Error::Json(json.clone()))
+}
+fn join_namespaces(namespaces: &[i32]) -> Option<String> {
+    if namespaces.is_empty() {
+        return None;
+    }
+    let namespaces = namespaces
+        .iter()
+        .map(|ns| ns.to_string())
+        .collect::<Vec<_>>();
+    Some(namespaces.join("|"))
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub struct Title {
+    pub ns: i32,
+    pub title: String,
+}
+#[derive(Debug, Deserialize)]
+pub struct Change {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub ns: i32,
+    pub title: String,
+    #[serde(default)]
+    pub pageid: u64,
+    #[serde(default)]
+    pub revid: u64,
+    #[serde(default)]
+    pub old_revid: u64,
+    pub rcid: Option<u64>,
+    #[serde(default)]
+    pub user: String,
+    #[serde(default)]
+    pub userid: u64,
+    #[serde(default)]
+    pub anon: bool,
+    #[serde(default)]
+    pub bot: bool,
+    #[serde(default)]
+    pub new: bool,
+    #[serde(default)]
+    pub minor: bool,
+    #[serde(default)]
+    pub redirect: bool,
+    pub oldlen: Option<u64>,
+    pub newlen: Option<u64>,
+    pub timestamp: String,
+    #[serde(default)]
+    pub comment: String,
+    pub logid: Option<u64>,
+    pub logtype: Option<String>,
+    pub logaction: Option<String>,
+    #[serde(default)]
+    pub logparams: Json,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub sha1: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct Config {
     useragent: String,
@@ -109,7 +207,7 @@ impl Mediawiki {
             _ => Err(json.clone().into()),
         }
     }
-    pub fn request(&self) -> RequestBuilder {
+    pub fn request(&self) -> RequestBuilder<'_> {
         RequestBuilder::new(self)
     }
     pub fn get_token<T>(&self) -> Result<Token<T>, Error>
@@ -128,14 +226,18 @@ impl Mediawiki {
                 .ok_or_else(|| Error::Json(json.clone()))?,
         ))
     }
-    pub fn query<T: Into<String>>(&self, list: T) -> QueryBuilder {
+    pub fn query<T: Into<String>>(&self, list: T) -> QueryBuilder<'_> {
         let list = list.into();
         let mut request = self.request();
         request.arg("action", "query");
         request.arg("continue", "");
-        QueryBuilder { req: request, list }
+        QueryBuilder {
+            req: request,
+            list,
+            item: PhantomData,
+        }
     }
-    pub fn query_recentchanges(&self, limit: u32) -> QueryBuilder {
+    pub fn query_recentchanges(&self, limit: u32) -> QueryBuilder<'_> {
         let mut query = self.query("recentchanges");
         query.arg("list", "recentchanges");
         query.arg("rcdir", "older");
@@ -146,68 +248,6 @@ impl Mediawiki {
         query.arg("limit", limit.to_string());
         query
     }
-    pub fn download_file(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
-        let mut request = self.request();
-        request.arg("action", "query");
-        request.arg("prop", "imageinfo");
-        request.arg("titles", format!("File:{name}"));
-        request.arg("iiprop", "url");
-        let json = request.get()?;
-        let images = json["query"]["pages"]
-            .as_array()
-            .ok_or_else(|| Error::Json(json.clone()))?;
-        let image = images.first().ok_or_else(|| Error::Json(json.clone()))?;
-        if !image["missing"].is_null() {
-            return Ok(None);
-        }
-        let url = image["imageinfo"][0]["url"]
-            .as_str()
-            .ok_or_else(|| Error::Json(image.clone()))?;
-        let mut response = loop {
-            let mut request = self.client.request(Method::GET, url);
-            request = request.header(USER_AGENT, &*self.config.useragent);
-            let response = request.send()?;
-            if response.status() == StatusCode::OK {
-                break response;
-            }
-            println!("{response:?}");
-            sleep(Duration::from_secs(5))
-        };
-        let mut buf = Vec::new();
-        response.read_to_end(&mut buf)?;
-        Ok(Some(buf))
-    }
-    pub fn upload(
-        &self,
-        filename: &str,
-        token: &Token<Csrf>,
-        file: Upload,
-        text: Option<&str>,
-        comment: Option<&str>,
-        ignorewarnings: bool,
-    ) -> Result<Json, Error> {
-        let request = self.request();
-        let mut form = Form::new()
-            .text("format", "json")
-            .text("action", "upload")
-            .text("filename", filename.to_owned())
-            .text("token", token.0.clone());
-        if let Some(text) = text {
-            form = form.text("text", text.to_owned());
-        }
-        if let Some(comment) = comment {
-            form = form.text("comment", comment.to_owned());
-        }
-        if ignorewarnings {
-            form = form.text("ignorewarnings", "true");
-        }
-        form = match file {
-            Upload::File(file) => form.file("file", file)?,
-            Upload::Filekey(filekey) => form.text("filekey", filekey.to_owned()),
-            Upload::Url(url) => form.text("url", url.to_owned()),
-        };
-        request.multipart(form)
-    }
 }
 #[derive(Clone)]
 pub struct RequestBuilder<'a> {
@@ -242,6 +282,15 @@ impl<'a> RequestBuilder<'a> {
         }
         self
     }
+    pub fn flag<T>(&mut self, key: T, val: bool) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        if val {
+            self.args.insert(key.into(), "1".into());
+        }
+        self
+    }
     fn request(&self, method: Method, multipart: Option<Form>) -> Result<Json, Error> {
         let mut request = self
             .mw
@@ -278,7 +327,10 @@ impl<'a> RequestBuilder<'a> {
         if status.is_success() {
             let json: Json = serde_json::from_str(&text)?;
             if json["error"].is_object() {
-                Err(Error::Json(json))
+                match ApiError::deserialize(&json["error"]) {
+                    Ok(err) => Err(Error::Api(err)),
+                    Err(_) => Err(Error::Json(json)),
+                }
             } else {
                 Ok(json)
             }
@@ -291,7 +343,7 @@ impl<'a> RequestBuilder<'a> {
         loop {
             match self.request(Method::POST, None) {
                 Ok(json) => return Ok(json),
-                Err(Error::Json(err)) => return Err(Error::Json(err)),
+                Err(err @ (Error::Api(_) | Error::Json(_))) => return Err(err),
                 Err(status) => println!("{status:?}"),
             }
         }
@@ -300,10 +352,24 @@ impl<'a> RequestBuilder<'a> {
         loop {
             match self.request(Method::GET, None) {
                 Ok(json) => return Ok(json),
+                Err(Error::Api(err)) => return Err(Error::Api(err)),
                 Err(status) => println!("{status:?}"),
             }
         }
     }
+    fn get_bounded(&self, retries: u32) -> Result<Json, Error> {
+        let mut attempt = 0;
+        loop {
+            match self.request(Method::GET, None) {
+                Ok(json) => return Ok(json),
+                Err(err @ (Error::Api(_) | Error::Json(_))) => return Err(err),
+                Err(err) if attempt >= retries => return Err(err),
+                Err(status) => println!("{status:?}"),
+            }
+            attempt += 1;
+            sleep(Duration::from_secs(5));
+        }
+    }
     fn multipart(&self, multipart: Form) -> Result<Json, Error> {
         self.request(Method::POST, Some(multipart))
     }
@@ -313,54 +379,80 @@ pub enum Upload<'a> {
     Filekey(&'a str),
     Url(&'a str),
 }
-#[derive(Clone)]
-pub struct QueryBuilder<'a> {
+pub struct QueryBuilder<'a, T = Json> {
     req: RequestBuilder<'a>,
     list: String,
+    item: PhantomData<fn() -> T>,
 }
-impl<'a> QueryBuilder<'a> {
-    pub fn arg<T, U>(&mut self, key: T, val: U) -> &mut Self
+impl<'a, T> Clone for QueryBuilder<'a, T> {
+    fn clone(&self) -> Self {
+        QueryBuilder {
+            req: self.req.clone(),
+            list: self.list.clone(),
+            item: PhantomData,
+        }
+    }
+}
+impl<'a, T> QueryBuilder<'a, T> {
+    pub fn arg<K, V>(&mut self, key: K, val: V) -> &mut Self
     where
-        T: Into<String>,
-        U: Into<String>,
+        K: Into<String>,
+        V: Into<String>,
     {
         self.req.arg(key, val);
         self
     }
-    pub fn argo<T, U>(&mut self, key: T, val: Option<U>) -> &mut Self
+    pub fn argo<K, V>(&mut self, key: K, val: Option<V>) -> &mut Self
     where
-        T: Into<String>,
-        U: Into<String>,
+        K: Into<String>,
+        V: Into<String>,
     {
         self.req.argo(key, val);
         self
     }
+    pub fn flag<K>(&mut self, key: K, val: bool) -> &mut Self
+    where
+        K: Into<String>,
+    {
+        self.req.flag(key, val);
+        self
+    }
+    pub fn typed<U>(self) -> QueryBuilder<'a, U> {
+        QueryBuilder {
+            req: self.req,
+            list: self.list,
+            item: PhantomData,
+        }
+    }
 }
-impl<'a> IntoIterator for QueryBuilder<'a> {
-    type Item = Result<Json, Error>;
-    type IntoIter = Query<'a>;
-    fn into_iter(self) -> Query<'a> {
+impl<'a, T: DeserializeOwned> IntoIterator for QueryBuilder<'a, T> {
+    type Item = Result<T, Error>;
+    type IntoIter = Query<'a, T>;
+    fn into_iter(self) -> Query<'a, T> {
         Query {
             req: self.req,
             list: self.list,
             buf: Vec::new(),
             done: false,
+            item: PhantomData,
         }
     }
 }
-pub struct Query<'a> {
+pub struct Query<'a, T = Json> {
     req: RequestBuilder<'a>,
     list: String,
     buf: Vec<Json>,
     done: bool,
+    item: PhantomData<fn() -> T>,
 }
-impl<'a> Query<'a> {
+impl<'a, T> Query<'a, T> {
     fn fill(&mut self) -> Result<bool, Error> {
         let json = self.req.get()?;
-        let buf = json["query"][&self.list]
-            .as_array()
-            .ok_or_else(|| Error::Json(json.clone()))?;
-        self.buf.clone_from(buf);
+        match &json["query"][&self.list] {
+            Json::Array(buf) => self.buf.clone_from(buf),
+            Json::Null if self.list == "pages" || json["query"].is_null() => self.buf.clear(),
+            _ => return Err(Error::Json(json.clone())),
+        }
         self.buf.reverse();
         if let Json::Object(cont) = &json["continue"] {
             for (key, val) in cont {
@@ -373,10 +465,10 @@ impl<'a> Query<'a> {
         }
     }
 }
-impl<'a> Iterator for Query<'a> {
-    type Item = Result<Json, Error>;
-    fn next(&mut self) -> Option<Result<Json, Error>> {
-        if self.buf.is_empty() {
+impl<'a, T: DeserializeOwned> Iterator for Query<'a, T> {
+    type Item = Result<T, Error>;
+    fn next(&mut self) -> Option<Result<T, Error>> {
+        while self.buf.is_empty() {
             if self.done {
                 return None;
             }
@@ -386,7 +478,9 @@ impl<'a> Iterator for Query<'a> {
                 Ok(true) => (),
             }
         }
-        self.buf.pop().map(Ok)
+        self.buf
+            .pop()
+            .map(|json| T::deserialize(&json).map_err(|_| Error::Json(json.clone())))
     }
 }
 pub trait TokenType {
@@ -0,0 +1,97 @@
+use crate::{join_namespaces, Mediawiki, QueryBuilder, Title};
+
+#[derive(Clone, Copy)]
+pub enum CategoryMemberType {
+    Page,
+    Subcat,
+    File,
+}
+impl CategoryMemberType {
+    fn as_str(self) -> &'static str {
+        match self {
+            CategoryMemberType::Page => "page",
+            CategoryMemberType::Subcat => "subcat",
+            CategoryMemberType::File => "file",
+        }
+    }
+}
+#[derive(Clone, Copy)]
+pub enum CategorySort {
+    Sortkey,
+    Timestamp,
+}
+impl Mediawiki {
+    pub fn query_backlinks(&self, title: &str, namespaces: &[i32]) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("backlinks").typed();
+        query.arg("list", "backlinks");
+        query.arg("bltitle", title);
+        query.arg("bllimit", "max");
+        query.argo("blnamespace", join_namespaces(namespaces));
+        query
+    }
+    pub fn query_embeddedin(&self, title: &str, namespaces: &[i32]) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("embeddedin").typed();
+        query.arg("list", "embeddedin");
+        query.arg("eititle", title);
+        query.arg("eilimit", "max");
+        query.argo("einamespace", join_namespaces(namespaces));
+        query
+    }
+    pub fn query_imageusage(&self, title: &str, namespaces: &[i32]) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("imageusage").typed();
+        query.arg("list", "imageusage");
+        query.arg("iutitle", title);
+        query.arg("iulimit", "max");
+        query.argo("iunamespace", join_namespaces(namespaces));
+        query
+    }
+    pub fn query_categorymembers(
+        &self,
+        category: &str,
+        types: &[CategoryMemberType],
+        sort: CategorySort,
+        namespaces: &[i32],
+    ) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("categorymembers").typed();
+        query.arg("list", "categorymembers");
+        query.arg("cmtitle", category);
+        query.arg("cmlimit", "max");
+        if !types.is_empty() {
+            let types = types.iter().map(|t| t.as_str()).collect::<Vec<_>>();
+            query.arg("cmtype", types.join("|"));
+        }
+        query.arg(
+            "cmsort",
+            match sort {
+                CategorySort::Sortkey => "sortkey",
+                CategorySort::Timestamp => "timestamp",
+            },
+        );
+        query.argo("cmnamespace", join_namespaces(namespaces));
+        query
+    }
+    fn query_generator(
+        &self,
+        title: &str,
+        generator: &str,
+        limit: &str,
+    ) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("pages").typed();
+        query.arg("generator", generator);
+        query.arg("titles", title);
+        query.arg(limit, "max");
+        query
+    }
+    pub fn query_links(&self, title: &str) -> QueryBuilder<'_, Title> {
+        self.query_generator(title, "links", "gpllimit")
+    }
+    pub fn query_templates(&self, title: &str) -> QueryBuilder<'_, Title> {
+        self.query_generator(title, "templates", "gtllimit")
+    }
+    pub fn query_categories(&self, title: &str) -> QueryBuilder<'_, Title> {
+        self.query_generator(title, "categories", "gcllimit")
+    }
+    pub fn query_images(&self, title: &str) -> QueryBuilder<'_, Title> {
+        self.query_generator(title, "images", "gimlimit")
+    }
+}
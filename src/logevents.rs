@@ -0,0 +1,319 @@
+use crate::{Json, Mediawiki, QueryBuilder};
+use serde::{de::DeserializeOwned, Deserialize};
+
+#[derive(Default)]
+pub struct LogEventsOptions<'a> {
+    pub kind: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub user: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub start: Option<&'a str>,
+    pub end: Option<&'a str>,
+    pub newer: bool,
+}
+#[derive(Debug, Deserialize)]
+#[serde(from = "RawLogEntry")]
+pub struct LogEntry {
+    pub logid: u64,
+    pub ns: i32,
+    pub title: String,
+    pub pageid: u64,
+    pub logpage: u64,
+    pub user: String,
+    pub userid: u64,
+    pub timestamp: String,
+    pub comment: String,
+    pub tags: Vec<String>,
+    pub action: String,
+    pub details: LogDetails,
+}
+#[derive(Debug)]
+pub enum LogDetails {
+    Upload(UploadParams),
+    Move(MoveParams),
+    Delete,
+    Block(BlockParams),
+    Rights(RightsParams),
+    Tilesheet(TilesheetLog),
+    OreDict(OreDictParams),
+    Other { kind: String, params: Json },
+}
+#[derive(Debug, Deserialize)]
+pub struct UploadParams {
+    pub img_sha1: String,
+    pub img_timestamp: String,
+}
+#[derive(Debug, Deserialize)]
+pub struct MoveParams {
+    pub target_ns: i32,
+    pub target_title: String,
+    #[serde(default)]
+    pub suppressredirect: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct BlockParams {
+    pub duration: Option<String>,
+    #[serde(default)]
+    pub flags: Vec<String>,
+    pub expiry: Option<String>,
+    #[serde(default)]
+    pub sitewide: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct RightsParams {
+    #[serde(default)]
+    pub oldgroups: Vec<String>,
+    #[serde(default)]
+    pub newgroups: Vec<String>,
+}
+#[derive(Debug)]
+pub enum TilesheetLog {
+    Sheet(SheetLogParams),
+    Tile(TileLogParams),
+    Translation(TranslationLogParams),
+}
+#[derive(Debug, Deserialize)]
+pub struct SheetLogParams {
+    #[serde(rename = "mod")]
+    pub mod_name: String,
+    pub sizes: Option<String>,
+    pub to_mod: Option<String>,
+    pub to_sizes: Option<String>,
+}
+#[derive(Debug, Deserialize)]
+pub struct TileLogParams {
+    pub id: Option<i64>,
+    #[serde(rename = "mod")]
+    pub mod_name: Option<String>,
+    pub name: Option<String>,
+    pub x: Option<u32>,
+    pub y: Option<u32>,
+    pub z: Option<u32>,
+    pub to_mod: Option<String>,
+    pub to_name: Option<String>,
+    pub to_x: Option<u32>,
+    pub to_y: Option<u32>,
+    pub to_z: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct TranslationLogParams {
+    pub id: i64,
+    pub lang: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+#[derive(Debug, Deserialize)]
+pub struct OreDictParams {
+    pub id: Option<i64>,
+    #[serde(rename = "mod")]
+    pub mod_name: Option<String>,
+    pub tag: Option<String>,
+    pub item: Option<String>,
+    pub params: Option<String>,
+    pub to_mod: Option<String>,
+    pub to_tag: Option<String>,
+    pub to_item: Option<String>,
+    pub to_params: Option<String>,
+}
+#[derive(Deserialize)]
+struct RawLogEntry {
+    #[serde(default)]
+    logid: u64,
+    #[serde(default)]
+    ns: i32,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    pageid: u64,
+    #[serde(default)]
+    logpage: u64,
+    #[serde(default)]
+    user: String,
+    #[serde(default)]
+    userid: u64,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    comment: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(rename = "type")]
+    kind: String,
+    action: String,
+    #[serde(default)]
+    params: Json,
+}
+fn params<T: DeserializeOwned>(params: &Json) -> Option<T> {
+    T::deserialize(params).ok()
+}
+impl From<RawLogEntry> for LogEntry {
+    fn from(raw: RawLogEntry) -> LogEntry {
+        let details = match &*raw.kind {
+            "upload" => params(&raw.params).map(LogDetails::Upload),
+            "move" => params(&raw.params).map(LogDetails::Move),
+            "delete" => Some(LogDetails::Delete),
+            "block" => params(&raw.params).map(LogDetails::Block),
+            "rights" => params(&raw.params).map(LogDetails::Rights),
+            "tilesheet" => match &*raw.action {
+                "createsheet" | "editsheet" | "deletesheet" => {
+                    params(&raw.params).map(TilesheetLog::Sheet)
+                }
+                "createtile" | "edittile" | "deletetile" => {
+                    params(&raw.params).map(TilesheetLog::Tile)
+                }
+                "translatetile" | "deletetranslation" => {
+                    params(&raw.params).map(TilesheetLog::Translation)
+                }
+                _ => None,
+            }
+            .map(LogDetails::Tilesheet),
+            "oredict" => params(&raw.params).map(LogDetails::OreDict),
+            _ => None,
+        };
+        let details = details.unwrap_or(LogDetails::Other {
+            kind: raw.kind,
+            params: raw.params,
+        });
+        LogEntry {
+            logid: raw.logid,
+            ns: raw.ns,
+            title: raw.title,
+            pageid: raw.pageid,
+            logpage: raw.logpage,
+            user: raw.user,
+            userid: raw.userid,
+            timestamp: raw.timestamp,
+            comment: raw.comment,
+            tags: raw.tags,
+            action: raw.action,
+            details,
+        }
+    }
+}
+impl Mediawiki {
+    pub fn query_logevents(&self, options: &LogEventsOptions) -> QueryBuilder<'_, LogEntry> {
+        let mut query = self.query("logevents").typed();
+        query.arg("list", "logevents");
+        query.arg("lelimit", "max");
+        query.arg(
+            "leprop",
+            "ids|title|type|user|userid|timestamp|comment|details|tags",
+        );
+        let action = match (options.kind, options.action) {
+            (Some(kind), Some(action)) if !action.contains('/') => Some(format!("{kind}/{action}")),
+            (_, action) => action.map(Into::into),
+        };
+        query.argo("letype", options.kind);
+        query.argo("leaction", action);
+        query.argo("leuser", options.user);
+        query.argo("letitle", options.title);
+        query.argo("lestart", options.start);
+        query.argo("leend", options.end);
+        query.arg("ledir", if options.newer { "newer" } else { "older" });
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Json, LogDetails, LogEntry, TilesheetLog};
+    use serde_json::{from_value, json};
+
+    fn entry(kind: &str, action: &str, params: Json) -> LogDetails {
+        let entry: LogEntry = from_value(json!({
+            "logid": 1,
+            "title": "Example",
+            "type": kind,
+            "action": action,
+            "params": params,
+        }))
+        .unwrap();
+        entry.details
+    }
+    #[test]
+    fn upload() {
+        let details = entry(
+            "upload",
+            "overwrite",
+            json!({"img_sha1": "abc", "img_timestamp": "2020-01-01T00:00:00Z"}),
+        );
+        let LogDetails::Upload(params) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.img_sha1, "abc");
+    }
+    #[test]
+    fn move_page() {
+        let details = entry(
+            "move",
+            "move",
+            json!({"target_ns": 0, "target_title": "Other", "suppressredirect": true}),
+        );
+        let LogDetails::Move(params) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.target_title, "Other");
+        assert!(params.suppressredirect);
+    }
+    #[test]
+    fn tilesheet_sheet() {
+        let details = entry(
+            "tilesheet",
+            "editsheet",
+            json!({"mod": "A", "to_mod": "B", "to_sizes": "16,32"}),
+        );
+        let LogDetails::Tilesheet(TilesheetLog::Sheet(params)) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.mod_name, "A");
+        assert_eq!(params.to_mod.as_deref(), Some("B"));
+    }
+    #[test]
+    fn tilesheet_tile() {
+        let details = entry(
+            "tilesheet",
+            "edittile",
+            json!({"id": 5, "mod": "A", "name": "Stone", "to_x": 3}),
+        );
+        let LogDetails::Tilesheet(TilesheetLog::Tile(params)) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.id, Some(5));
+        assert_eq!(params.to_x, Some(3));
+    }
+    #[test]
+    fn tilesheet_translation() {
+        let details = entry(
+            "tilesheet",
+            "translatetile",
+            json!({"id": 5, "lang": "fr", "name": "Pierre"}),
+        );
+        let LogDetails::Tilesheet(TilesheetLog::Translation(params)) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.lang, "fr");
+        assert_eq!(params.name.as_deref(), Some("Pierre"));
+    }
+    #[test]
+    fn oredict() {
+        let details = entry(
+            "oredict",
+            "editentry",
+            json!({"id": 7, "mod": "A", "tag": "ingotIron", "to_tag": "ingotSteel"}),
+        );
+        let LogDetails::OreDict(params) = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(params.tag.as_deref(), Some("ingotIron"));
+        assert_eq!(params.to_tag.as_deref(), Some("ingotSteel"));
+    }
+    #[test]
+    fn unknown_falls_back() {
+        let details = entry("tilesheet", "unknown", json!({"mod": "A"}));
+        let LogDetails::Other { kind, params } = details else {
+            panic!("{details:?}");
+        };
+        assert_eq!(kind, "tilesheet");
+        assert_eq!(params["mod"], "A");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{extract, Csrf, Error, Mediawiki, Patrol, Rollback, Token};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct RollbackResult {
+    pub title: String,
+    pub pageid: u64,
+    #[serde(default)]
+    pub summary: String,
+    pub revid: u64,
+    pub old_revid: u64,
+    pub last_revid: u64,
+}
+#[derive(Debug, Deserialize)]
+pub struct EditResult {
+    pub result: String,
+    pub pageid: Option<u64>,
+    pub title: Option<String>,
+    pub oldrevid: Option<u64>,
+    pub newrevid: Option<u64>,
+    pub newtimestamp: Option<String>,
+    #[serde(default)]
+    pub nochange: bool,
+}
+pub enum PatrolTarget {
+    Rcid(u64),
+    Revid(u64),
+}
+#[derive(Debug, Deserialize)]
+pub struct PatrolResult {
+    pub rcid: u64,
+    pub ns: i32,
+    pub title: String,
+}
+impl Mediawiki {
+    pub fn rollback(
+        &self,
+        token: &Token<Rollback>,
+        title: &str,
+        user: &str,
+        summary: Option<&str>,
+    ) -> Result<RollbackResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "rollback");
+        request.arg("token", token.value());
+        request.arg("title", title);
+        request.arg("user", user);
+        request.argo("summary", summary);
+        extract(&request.post()?, "rollback")
+    }
+    pub fn undo(
+        &self,
+        token: &Token<Csrf>,
+        title: &str,
+        revid: u64,
+        undoafter: Option<u64>,
+        summary: Option<&str>,
+    ) -> Result<EditResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "edit");
+        request.arg("token", token.value());
+        request.arg("title", title);
+        request.arg("undo", revid.to_string());
+        request.argo("undoafter", undoafter.map(|id| id.to_string()));
+        request.argo("summary", summary);
+        request.flag("nocreate", true);
+        extract(&request.post()?, "edit")
+    }
+    pub fn patrol(
+        &self,
+        token: &Token<Patrol>,
+        target: PatrolTarget,
+    ) -> Result<PatrolResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "patrol");
+        request.arg("token", token.value());
+        match target {
+            PatrolTarget::Rcid(rcid) => request.arg("rcid", rcid.to_string()),
+            PatrolTarget::Revid(revid) => request.arg("revid", revid.to_string()),
+        };
+        extract(&request.post()?, "patrol")
+    }
+    pub fn patrol_trusted(
+        &self,
+        token: &Token<Patrol>,
+        trusted: &[&str],
+    ) -> Result<Vec<PatrolResult>, Error> {
+        let mut query = self.query_recentchanges(500);
+        query.arg("rcshow", "!patrolled");
+        let mut patrolled = Vec::new();
+        for change in query {
+            let change = change?;
+            let user = change["user"].as_str().unwrap_or_default();
+            if !trusted.contains(&user) {
+                continue;
+            }
+            let rcid = change["rcid"]
+                .as_u64()
+                .ok_or_else(|| Error::Json(change.clone()))?;
+            patrolled.push(self.patrol(token, PatrolTarget::Rcid(rcid))?);
+        }
+        Ok(patrolled)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{Csrf, Error, Json, Mediawiki, QueryBuilder, Token};
 pub trait Oredict {
-    fn query_ores(&self, odmod: Option<&str>) -> QueryBuilder;
+    fn query_ores(&self, odmod: Option<&str>) -> QueryBuilder<'_>;
     fn delete_ores(&self, token: &Token<Csrf>, ids: &str) -> Result<Json, Error>;
     fn edit_ore(
         &self,
@@ -13,7 +13,7 @@ pub trait Oredict {
     ) -> Result<Json, Error>;
 }
 impl Oredict for Mediawiki {
-    fn query_ores(&self, odmod: Option<&str>) -> QueryBuilder {
+    fn query_ores(&self, odmod: Option<&str>) -> QueryBuilder<'_> {
         let mut query = self.query("oredictentries");
         query.arg("list", "oredictsearch");
         query.arg("odlimit", "5000");
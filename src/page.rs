@@ -0,0 +1,196 @@
+use crate::{extract, ApiError, Csrf, Error, Mediawiki, Token};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct MoveOptions<'a> {
+    pub reason: Option<&'a str>,
+    pub movetalk: bool,
+    pub movesubpages: bool,
+    pub noredirect: bool,
+    pub ignorewarnings: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct MoveResult {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub redirectcreated: bool,
+    #[serde(default)]
+    pub moveoverredirect: bool,
+    pub talkfrom: Option<String>,
+    pub talkto: Option<String>,
+    #[serde(rename = "talkmove-errors")]
+    pub talkmove_errors: Option<Vec<ApiError>>,
+    #[serde(default)]
+    pub subpages: Vec<MovedSubpage>,
+    #[serde(default, rename = "subpages-talk")]
+    pub subpages_talk: Vec<MovedSubpage>,
+}
+#[derive(Debug, Deserialize)]
+pub struct MovedSubpage {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub errors: Option<Vec<ApiError>>,
+}
+#[derive(Debug, Deserialize)]
+pub struct DeleteResult {
+    pub title: String,
+    #[serde(default)]
+    pub reason: String,
+    pub logid: u64,
+}
+#[derive(Default)]
+pub struct UndeleteOptions<'a> {
+    pub reason: Option<&'a str>,
+    pub timestamps: &'a [&'a str],
+    pub fileids: &'a [u64],
+    pub undeletetalk: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct UndeleteResult {
+    pub title: String,
+    pub revisions: u64,
+    pub fileversions: u64,
+    #[serde(default)]
+    pub reason: String,
+}
+#[derive(Default)]
+pub struct ProtectOptions<'a> {
+    pub reason: Option<&'a str>,
+    pub expiry: &'a [&'a str],
+    pub cascade: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct ProtectResult {
+    pub title: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub cascade: bool,
+    pub protections: Vec<Protection>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "HashMap<String, String>")]
+pub struct Protection {
+    pub kind: String,
+    pub level: String,
+    pub expiry: String,
+}
+impl TryFrom<HashMap<String, String>> for Protection {
+    type Error = String;
+    fn try_from(mut map: HashMap<String, String>) -> Result<Protection, String> {
+        let expiry = map
+            .remove("expiry")
+            .ok_or_else(|| "missing expiry".to_owned())?;
+        let (kind, level) = map
+            .into_iter()
+            .next()
+            .ok_or_else(|| "missing protection type".to_owned())?;
+        Ok(Protection {
+            kind,
+            level,
+            expiry,
+        })
+    }
+}
+impl Mediawiki {
+    pub fn page_content(&self, title: &str) -> Result<Option<String>, Error> {
+        let mut request = self.request();
+        request.arg("action", "query");
+        request.arg("prop", "revisions");
+        request.arg("titles", title);
+        request.arg("rvprop", "content");
+        request.arg("rvslots", "main");
+        let json = request.get()?;
+        let page = &json["query"]["pages"][0];
+        if page.is_null() || !page["missing"].is_null() {
+            return Ok(None);
+        }
+        Ok(page["revisions"][0]["slots"]["main"]["content"]
+            .as_str()
+            .map(Into::into))
+    }
+    pub fn move_page(
+        &self,
+        token: &Token<Csrf>,
+        from: &str,
+        to: &str,
+        options: &MoveOptions,
+    ) -> Result<MoveResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "move");
+        request.arg("token", token.value());
+        request.arg("from", from);
+        request.arg("to", to);
+        request.argo("reason", options.reason);
+        request.flag("movetalk", options.movetalk);
+        request.flag("movesubpages", options.movesubpages);
+        request.flag("noredirect", options.noredirect);
+        request.flag("ignorewarnings", options.ignorewarnings);
+        extract(&request.post()?, "move")
+    }
+    pub fn delete_page(
+        &self,
+        token: &Token<Csrf>,
+        title: &str,
+        reason: Option<&str>,
+    ) -> Result<DeleteResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "delete");
+        request.arg("token", token.value());
+        request.arg("title", title);
+        request.argo("reason", reason);
+        extract(&request.post()?, "delete")
+    }
+    pub fn undelete_page(
+        &self,
+        token: &Token<Csrf>,
+        title: &str,
+        options: &UndeleteOptions,
+    ) -> Result<UndeleteResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "undelete");
+        request.arg("token", token.value());
+        request.arg("title", title);
+        request.argo("reason", options.reason);
+        if !options.timestamps.is_empty() {
+            request.arg("timestamps", options.timestamps.join("|"));
+        }
+        if !options.fileids.is_empty() {
+            let fileids = options
+                .fileids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>();
+            request.arg("fileids", fileids.join("|"));
+        }
+        request.flag("undeletetalk", options.undeletetalk);
+        extract(&request.post()?, "undelete")
+    }
+    pub fn protect_page(
+        &self,
+        token: &Token<Csrf>,
+        title: &str,
+        protections: &[(&str, &str)],
+        options: &ProtectOptions,
+    ) -> Result<ProtectResult, Error> {
+        let protections = protections
+            .iter()
+            .map(|(kind, level)| format!("{kind}={level}"))
+            .collect::<Vec<_>>();
+        let mut request = self.request();
+        request.arg("action", "protect");
+        request.arg("token", token.value());
+        request.arg("title", title);
+        request.arg("protections", protections.join("|"));
+        if !options.expiry.is_empty() {
+            request.arg("expiry", options.expiry.join("|"));
+        }
+        request.argo("reason", options.reason);
+        request.flag("cascade", options.cascade);
+        extract(&request.post()?, "protect")
+    }
+}
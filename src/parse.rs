@@ -0,0 +1,82 @@
+use crate::{extract, Error, Mediawiki, Title};
+use serde::Deserialize;
+
+pub enum ParseInput<'a> {
+    Title(&'a str),
+    Text {
+        text: &'a str,
+        title: Option<&'a str>,
+    },
+    Revid(u64),
+}
+#[derive(Debug, Deserialize)]
+pub struct ParseResult {
+    pub title: String,
+    #[serde(default)]
+    pub pageid: u64,
+    #[serde(default)]
+    pub revid: u64,
+    #[serde(default)]
+    pub displaytitle: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub categories: Vec<ParsedCategory>,
+    #[serde(default)]
+    pub templates: Vec<Title>,
+    #[serde(default)]
+    pub links: Vec<Title>,
+    #[serde(default)]
+    pub images: Vec<String>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+}
+#[derive(Debug, Deserialize)]
+pub struct ParsedCategory {
+    pub category: String,
+    #[serde(default)]
+    pub sortkey: String,
+    #[serde(default)]
+    pub hidden: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct Section {
+    pub toclevel: u32,
+    pub level: String,
+    pub line: String,
+    pub number: String,
+    pub index: String,
+    pub anchor: String,
+    pub byteoffset: Option<u64>,
+}
+impl Mediawiki {
+    pub fn parse(&self, input: ParseInput) -> Result<ParseResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "parse");
+        request.arg(
+            "prop",
+            "text|categories|templates|links|images|sections|displaytitle",
+        );
+        match input {
+            ParseInput::Title(title) => request.arg("page", title),
+            ParseInput::Text { text, title } => request
+                .arg("text", text)
+                .argo("title", title)
+                .arg("contentmodel", "wikitext"),
+            ParseInput::Revid(revid) => request.arg("oldid", revid.to_string()),
+        };
+        extract(&request.post()?, "parse")
+    }
+    pub fn expand_templates(&self, text: &str, title: Option<&str>) -> Result<String, Error> {
+        let mut request = self.request();
+        request.arg("action", "expandtemplates");
+        request.arg("prop", "wikitext");
+        request.arg("text", text);
+        request.argo("title", title);
+        let json = request.post()?;
+        json["expandtemplates"]["wikitext"]
+            .as_str()
+            .map(Into::into)
+            .ok_or_else(|| Error::Json(json.clone()))
+    }
+}
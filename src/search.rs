@@ -0,0 +1,94 @@
+use crate::{join_namespaces, Error, Mediawiki, QueryBuilder, Title};
+use serde::Deserialize;
+
+#[derive(Clone, Copy)]
+pub enum SearchWhat {
+    Text,
+    Title,
+    NearMatch,
+}
+#[derive(Default)]
+pub struct SearchOptions<'a> {
+    pub namespaces: &'a [i32],
+    pub what: Option<SearchWhat>,
+    pub sort: Option<&'a str>,
+    pub snippets: bool,
+}
+#[derive(Debug, Deserialize)]
+pub struct SearchResult {
+    pub ns: i32,
+    pub title: String,
+    #[serde(default)]
+    pub pageid: u64,
+    pub size: Option<u64>,
+    pub wordcount: Option<u64>,
+    pub timestamp: Option<String>,
+    pub snippet: Option<String>,
+    pub titlesnippet: Option<String>,
+}
+#[derive(Debug)]
+pub struct OpenSearchResult {
+    pub title: String,
+    pub description: String,
+    pub url: String,
+}
+pub fn insource_regex(pattern: &str) -> String {
+    format!("insource:/{}/", pattern.replace('/', "\\/"))
+}
+impl Mediawiki {
+    pub fn search(&self, search: &str, options: &SearchOptions) -> QueryBuilder<'_, SearchResult> {
+        let mut query = self.query("search").typed();
+        query.arg("list", "search");
+        query.arg("srsearch", search);
+        query.arg("srlimit", "max");
+        query.argo("srnamespace", join_namespaces(options.namespaces));
+        query.argo(
+            "srwhat",
+            options.what.map(|what| match what {
+                SearchWhat::Text => "text",
+                SearchWhat::Title => "title",
+                SearchWhat::NearMatch => "nearmatch",
+            }),
+        );
+        query.argo("srsort", options.sort);
+        if options.snippets {
+            query.arg("srprop", "size|wordcount|timestamp|snippet|titlesnippet");
+        } else {
+            query.arg("srprop", "size|wordcount|timestamp");
+        }
+        query
+    }
+    pub fn query_prefixsearch(&self, prefix: &str, namespaces: &[i32]) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("prefixsearch").typed();
+        query.arg("list", "prefixsearch");
+        query.arg("pssearch", prefix);
+        query.arg("pslimit", "max");
+        query.argo("psnamespace", join_namespaces(namespaces));
+        query
+    }
+    pub fn opensearch(
+        &self,
+        search: &str,
+        limit: u32,
+        namespaces: &[i32],
+    ) -> Result<Vec<OpenSearchResult>, Error> {
+        let mut request = self.request();
+        request.arg("action", "opensearch");
+        request.arg("search", search);
+        request.arg("limit", limit.to_string());
+        request.argo("namespace", join_namespaces(namespaces));
+        let json = request.get()?;
+        let titles = json[1]
+            .as_array()
+            .ok_or_else(|| Error::Json(json.clone()))?;
+        Ok(titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| OpenSearchResult {
+                title: title.as_str().unwrap_or_default().into(),
+                description: json[2][i].as_str().unwrap_or_default().into(),
+                url: json[3][i].as_str().unwrap_or_default().into(),
+            })
+            .collect())
+    }
+}
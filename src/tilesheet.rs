@@ -1,7 +1,278 @@
-use crate::{Csrf, Error, Json, Mediawiki, QueryBuilder, Token};
+use crate::{
+    extract, oredict::Oredict, page::MoveOptions, Csrf, Error, Json, Mediawiki, QueryBuilder, Token,
+};
+use regex::Regex;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::OnceLock,
+};
+
+const TSIMPORT_LIMIT: usize = 50;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Tile {
+    pub id: i64,
+    #[serde(rename = "mod")]
+    pub mod_name: String,
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Sheet {
+    #[serde(rename = "mod")]
+    pub mod_name: String,
+    #[serde(deserialize_with = "sizes")]
+    pub sizes: Vec<u32>,
+}
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct TileTranslation {
+    #[serde(rename = "entry_id")]
+    pub id: i64,
+    #[serde(rename = "language")]
+    pub lang: String,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+}
+#[derive(Clone, Debug)]
+pub struct NewTile {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+#[derive(Debug, Default)]
+pub struct AddTilesReport {
+    pub added: Vec<String>,
+    pub failed: Vec<(String, Json)>,
+}
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditTile {
+    pub id: i64,
+    pub summary: Option<String>,
+    pub name: Option<String>,
+    pub mod_name: Option<String>,
+    pub x: Option<u32>,
+    pub y: Option<u32>,
+    pub z: Option<u32>,
+}
+impl EditTile {
+    pub fn new(id: i64) -> EditTile {
+        EditTile {
+            id,
+            ..EditTile::default()
+        }
+    }
+    pub fn diff(before: &Tile, after: &Tile) -> EditTile {
+        fn changed<T: PartialEq + Clone>(before: &T, after: &T) -> Option<T> {
+            (before != after).then(|| after.clone())
+        }
+        EditTile {
+            id: before.id,
+            summary: None,
+            name: changed(&before.name, &after.name),
+            mod_name: changed(&before.mod_name, &after.mod_name),
+            x: changed(&before.x, &after.x),
+            y: changed(&before.y, &after.y),
+            z: changed(&before.z, &after.z),
+        }
+    }
+    pub fn summary<T: Into<String>>(mut self, summary: T) -> EditTile {
+        self.summary = Some(summary.into());
+        self
+    }
+    pub fn name<T: Into<String>>(mut self, name: T) -> EditTile {
+        self.name = Some(name.into());
+        self
+    }
+    pub fn mod_name<T: Into<String>>(mut self, mod_name: T) -> EditTile {
+        self.mod_name = Some(mod_name.into());
+        self
+    }
+    pub fn x(mut self, x: u32) -> EditTile {
+        self.x = Some(x);
+        self
+    }
+    pub fn y(mut self, y: u32) -> EditTile {
+        self.y = Some(y);
+        self
+    }
+    pub fn z(mut self, z: u32) -> EditTile {
+        self.z = Some(z);
+        self
+    }
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.mod_name.is_none()
+            && self.x.is_none()
+            && self.y.is_none()
+            && self.z.is_none()
+    }
+}
+#[derive(Debug, Deserialize)]
+pub struct EditTileResult {
+    pub id: i64,
+    pub name: Option<String>,
+    #[serde(rename = "mod")]
+    pub mod_name: Option<String>,
+    pub x: Option<u32>,
+    pub y: Option<u32>,
+    pub z: Option<u32>,
+}
+#[derive(Debug, Default)]
+pub struct TranslationReport {
+    pub translated: Vec<i64>,
+    pub unmatched: Vec<String>,
+    pub failed: Vec<(i64, Error)>,
+}
+pub fn validate_lang(lang: &str) -> Result<(), Error> {
+    static LANG: OnceLock<Regex> = OnceLock::new();
+    let regex = LANG.get_or_init(|| Regex::new("^[a-z]{2,3}(-[a-z0-9]{2,8})*$").unwrap());
+    if regex.is_match(lang) {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!("Invalid language code: {lang}")))
+    }
+}
+pub fn parse_lang(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, val)| (key.trim().to_owned(), val.trim().to_owned()))
+        .collect()
+}
+pub fn parse_json_lang(text: &str) -> Result<BTreeMap<String, String>, Error> {
+    Ok(serde_json::from_str(text)?)
+}
+#[derive(Debug, Default)]
+pub struct RenameReport {
+    pub sheet: bool,
+    pub files: Vec<String>,
+    pub tiles: usize,
+    pub ores: usize,
+}
+#[derive(Debug)]
+pub struct RenameError {
+    pub report: RenameReport,
+    pub error: Error,
+}
+struct RenamePlan<'a> {
+    from: &'a str,
+    to: &'a str,
+    summary: Option<&'a str>,
+    sizes: Vec<u32>,
+    tiles: Vec<Tile>,
+    ores: Vec<i64>,
+}
+fn validate_sizes(sizes: &[u32]) -> Result<String, Error> {
+    let unique = sizes.iter().collect::<HashSet<_>>();
+    if sizes.is_empty() || sizes.contains(&0) || unique.len() != sizes.len() {
+        return Err(Error::Invalid(format!("Invalid sheet sizes: {sizes:?}")));
+    }
+    let sizes = sizes
+        .iter()
+        .map(|size| size.to_string())
+        .collect::<Vec<_>>();
+    Ok(sizes.join(","))
+}
+fn validate_mod(tsmod: &str) -> Result<(), Error> {
+    if tsmod.is_empty() || tsmod.contains(|c: char| c == '|' || c.is_whitespace()) {
+        return Err(Error::Invalid(format!(
+            "Invalid mod abbreviation: {tsmod:?}"
+        )));
+    }
+    Ok(())
+}
+fn validate_tiles(tiles: &[NewTile]) -> Result<(), Error> {
+    let mut names = HashSet::new();
+    let mut coords = HashSet::new();
+    for tile in tiles {
+        if tile.name.is_empty()
+            || tile.name.trim() != tile.name
+            || tile.name.contains('|')
+            || tile.name.chars().any(char::is_control)
+        {
+            return Err(Error::Invalid(format!(
+                "Invalid tile name: {:?}",
+                tile.name
+            )));
+        }
+        if !names.insert(&tile.name) {
+            return Err(Error::Invalid(format!(
+                "Duplicate tile name: {}",
+                tile.name
+            )));
+        }
+        if !coords.insert((tile.x, tile.y, tile.z)) {
+            return Err(Error::Invalid(format!(
+                "Duplicate tile coordinates: {} {} {}",
+                tile.x, tile.y, tile.z
+            )));
+        }
+    }
+    Ok(())
+}
+fn tsimport(tiles: &[NewTile]) -> String {
+    tiles
+        .iter()
+        .map(|tile| format!("{} {} {} {}", tile.x, tile.y, tile.z, tile.name))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+impl RenamePlan<'_> {
+    fn apply(
+        &self,
+        mw: &Mediawiki,
+        token: &Token<Csrf>,
+        report: &mut RenameReport,
+    ) -> Result<(), Error> {
+        mw.edit_sheet(token, self.from, Some(self.to), None, self.summary)?;
+        report.sheet = true;
+        let options = MoveOptions {
+            reason: self.summary,
+            ..MoveOptions::default()
+        };
+        for size in &self.sizes {
+            let file = format!("File:Tilesheet {} {size}.png", self.from);
+            let target = format!("File:Tilesheet {} {size}.png", self.to);
+            mw.move_page(token, &file, &target, &options)?;
+            report.files.push(file);
+        }
+        for tile in &self.tiles {
+            let mut edit = EditTile::new(tile.id).mod_name(self.to);
+            edit.summary = self.summary.map(Into::into);
+            mw.edit_tile_typed(token, &edit)?;
+            report.tiles += 1;
+        }
+        for &id in &self.ores {
+            mw.edit_ore(token, id, Some(self.to), None, None, None)?;
+            report.ores += 1;
+        }
+        Ok(())
+    }
+}
+fn sizes<'de, D>(deserializer: D) -> Result<Vec<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Json::deserialize(deserializer)? {
+        Json::String(sizes) => sizes
+            .split(',')
+            .map(|size| size.trim().parse().map_err(D::Error::custom))
+            .collect(),
+        sizes => Vec::deserialize(sizes).map_err(D::Error::custom),
+    }
+}
 pub trait Tilesheet {
-    fn query_tiles(&self, tsmod: Option<&str>) -> QueryBuilder;
-    fn query_tile_translations(&self, tsid: i64) -> QueryBuilder;
+    fn query_tiles(&self, tsmod: Option<&str>) -> QueryBuilder<'_>;
+    fn query_tiles_typed(&self, tsmod: Option<&str>) -> QueryBuilder<'_, Tile>;
+    fn query_tile_translations(&self, tsid: i64) -> QueryBuilder<'_>;
+    fn query_tile_translations_typed(&self, tsid: i64) -> QueryBuilder<'_, TileTranslation>;
     fn add_tiles(
         &self,
         token: &Token<Csrf>,
@@ -9,6 +280,13 @@ pub trait Tilesheet {
         tsimport: &str,
         summary: Option<&str>,
     ) -> Result<Json, Error>;
+    fn add_tiles_typed(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: &str,
+        tiles: &[NewTile],
+        summary: Option<&str>,
+    ) -> Result<AddTilesReport, Error>;
     fn delete_sheet(
         &self,
         token: &Token<Csrf>,
@@ -21,7 +299,52 @@ pub trait Tilesheet {
         tsids: &str,
         summary: Option<&str>,
     ) -> Result<Json, Error>;
-    fn query_sheets(&self) -> QueryBuilder;
+    fn query_sheets(&self) -> QueryBuilder<'_>;
+    fn edit_sheet(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: &str,
+        tomod: Option<&str>,
+        tosizes: Option<&[u32]>,
+        summary: Option<&str>,
+    ) -> Result<Json, Error>;
+    fn rename_mod(
+        &self,
+        token: &Token<Csrf>,
+        from: &str,
+        to: &str,
+        summary: Option<&str>,
+    ) -> Result<RenameReport, Error>;
+    fn translate_tile(
+        &self,
+        token: &Token<Csrf>,
+        tsid: i64,
+        lang: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<Json, Error>;
+    fn delete_translation(&self, token: &Token<Csrf>, tsid: i64, lang: &str)
+        -> Result<Json, Error>;
+    fn import_translations(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: Option<&str>,
+        english: &BTreeMap<String, String>,
+        localized: &BTreeMap<String, String>,
+        lang: &str,
+    ) -> Result<TranslationReport, Error>;
+    fn edit_tile_typed(
+        &self,
+        token: &Token<Csrf>,
+        edit: &EditTile,
+    ) -> Result<EditTileResult, Error>;
+    fn edit_tile_diff(
+        &self,
+        token: &Token<Csrf>,
+        before: &Tile,
+        after: &Tile,
+    ) -> Result<Option<EditTileResult>, Error>;
+    fn query_sheets_typed(&self) -> QueryBuilder<'_, Sheet>;
     fn create_sheet(
         &self,
         token: &Token<Csrf>,
@@ -43,25 +366,34 @@ pub trait Tilesheet {
     ) -> Result<Json, Error>;
 }
 impl Tilesheet for Mediawiki {
-    fn query_tiles(&self, tsmod: Option<&str>) -> QueryBuilder {
+    fn query_tiles(&self, tsmod: Option<&str>) -> QueryBuilder<'_> {
         let mut query = self.query("tiles");
         query.arg("list", "tiles");
         query.arg("tslimit", "5000");
         query.argo("tsmod", tsmod);
         query
     }
-    fn query_sheets(&self) -> QueryBuilder {
+    fn query_sheets(&self) -> QueryBuilder<'_> {
         let mut query = self.query("tilesheets");
         query.arg("list", "tilesheets");
         query.arg("tslimit", "5000");
         query
     }
-    fn query_tile_translations(&self, tsid: i64) -> QueryBuilder {
+    fn query_tile_translations(&self, tsid: i64) -> QueryBuilder<'_> {
         let mut query = self.query("tiles");
         query.arg("list", "tiletranslations");
         query.arg("tsid", tsid.to_string());
         query
     }
+    fn query_tiles_typed(&self, tsmod: Option<&str>) -> QueryBuilder<'_, Tile> {
+        self.query_tiles(tsmod).typed()
+    }
+    fn query_sheets_typed(&self) -> QueryBuilder<'_, Sheet> {
+        self.query_sheets().typed()
+    }
+    fn query_tile_translations_typed(&self, tsid: i64) -> QueryBuilder<'_, TileTranslation> {
+        self.query_tile_translations(tsid).typed()
+    }
     fn delete_sheet(
         &self,
         token: &Token<Csrf>,
@@ -103,6 +435,27 @@ impl Tilesheet for Mediawiki {
         request.argo("tssummary", summary);
         request.post()
     }
+    fn add_tiles_typed(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: &str,
+        tiles: &[NewTile],
+        summary: Option<&str>,
+    ) -> Result<AddTilesReport, Error> {
+        validate_tiles(tiles)?;
+        let mut report = AddTilesReport::default();
+        for chunk in tiles.chunks(TSIMPORT_LIMIT) {
+            let json = self.add_tiles(token, tsmod, &tsimport(chunk), summary)?;
+            let results = &json["edit"]["addtiles"];
+            for tile in chunk {
+                match &results[&tile.name] {
+                    Json::Bool(true) => report.added.push(tile.name.clone()),
+                    other => report.failed.push((tile.name.clone(), other.clone())),
+                }
+            }
+        }
+        Ok(report)
+    }
     fn create_sheet(
         &self,
         token: &Token<Csrf>,
@@ -141,4 +494,221 @@ impl Tilesheet for Mediawiki {
         request.argo("tstoz", toz);
         request.post()
     }
+    fn edit_tile_typed(
+        &self,
+        token: &Token<Csrf>,
+        edit: &EditTile,
+    ) -> Result<EditTileResult, Error> {
+        if edit.is_empty() {
+            return Err(Error::Invalid(format!("No changes for tile {}", edit.id)));
+        }
+        let json = self.edit_tile(
+            token,
+            &edit.id.to_string(),
+            edit.summary.as_deref(),
+            edit.name.as_deref(),
+            edit.mod_name.as_deref(),
+            edit.x.map(|x| x.to_string()).as_deref(),
+            edit.y.map(|y| y.to_string()).as_deref(),
+            edit.z.map(|z| z.to_string()).as_deref(),
+        )?;
+        extract(&json["edit"], "edittile")
+    }
+    fn edit_tile_diff(
+        &self,
+        token: &Token<Csrf>,
+        before: &Tile,
+        after: &Tile,
+    ) -> Result<Option<EditTileResult>, Error> {
+        let edit = EditTile::diff(before, after);
+        if edit.is_empty() {
+            return Ok(None);
+        }
+        self.edit_tile_typed(token, &edit).map(Some)
+    }
+    fn translate_tile(
+        &self,
+        token: &Token<Csrf>,
+        tsid: i64,
+        lang: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<Json, Error> {
+        validate_lang(lang)?;
+        let mut request = self.request();
+        request.arg("action", "translatetile");
+        request.arg("tstoken", &*token.0);
+        request.arg("tsid", tsid.to_string());
+        request.arg("tslang", lang);
+        request.argo("tsname", name);
+        request.argo("tsdescription", description);
+        request.post()
+    }
+    fn delete_translation(
+        &self,
+        token: &Token<Csrf>,
+        tsid: i64,
+        lang: &str,
+    ) -> Result<Json, Error> {
+        validate_lang(lang)?;
+        let mut request = self.request();
+        request.arg("action", "deletetranslation");
+        request.arg("tstoken", &*token.0);
+        request.arg("tsid", tsid.to_string());
+        request.arg("tslang", lang);
+        request.post()
+    }
+    fn import_translations(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: Option<&str>,
+        english: &BTreeMap<String, String>,
+        localized: &BTreeMap<String, String>,
+        lang: &str,
+    ) -> Result<TranslationReport, Error> {
+        validate_lang(lang)?;
+        let mut tiles: HashMap<String, Vec<i64>> = HashMap::new();
+        for tile in self.query_tiles_typed(tsmod) {
+            let tile = tile?;
+            tiles.entry(tile.name).or_default().push(tile.id);
+        }
+        let mut report = TranslationReport::default();
+        for (key, name) in english {
+            let Some(translated) = localized.get(key) else {
+                continue;
+            };
+            let Some(ids) = tiles.get(name) else {
+                report.unmatched.push(name.clone());
+                continue;
+            };
+            for &id in ids {
+                match self.translate_tile(token, id, lang, Some(translated), None) {
+                    Ok(_) => report.translated.push(id),
+                    Err(e) => report.failed.push((id, e)),
+                }
+            }
+        }
+        Ok(report)
+    }
+    fn edit_sheet(
+        &self,
+        token: &Token<Csrf>,
+        tsmod: &str,
+        tomod: Option<&str>,
+        tosizes: Option<&[u32]>,
+        summary: Option<&str>,
+    ) -> Result<Json, Error> {
+        if let Some(tomod) = tomod {
+            validate_mod(tomod)?;
+        }
+        let tosizes = tosizes.map(validate_sizes).transpose()?;
+        let mut request = self.request();
+        request.arg("action", "editsheet");
+        request.arg("tstoken", &*token.0);
+        request.arg("tsmod", tsmod);
+        request.argo("tstomod", tomod);
+        request.argo("tstosizes", tosizes);
+        request.argo("tssummary", summary);
+        request.post()
+    }
+    fn rename_mod(
+        &self,
+        token: &Token<Csrf>,
+        from: &str,
+        to: &str,
+        summary: Option<&str>,
+    ) -> Result<RenameReport, Error> {
+        validate_mod(to)?;
+        if from == to {
+            return Err(Error::Invalid(format!("Cannot rename {from} to itself")));
+        }
+        let sheets = self
+            .query_sheets_typed()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        if sheets.iter().any(|sheet| sheet.mod_name == to) {
+            return Err(Error::Invalid(format!("Tilesheet {to} already exists")));
+        }
+        let sheet = sheets
+            .into_iter()
+            .find(|sheet| sheet.mod_name == from)
+            .ok_or_else(|| Error::Invalid(format!("Tilesheet {from} does not exist")))?;
+        let tiles = self
+            .query_tiles_typed(Some(from))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let ores = self
+            .query_ores(Some(from))
+            .into_iter()
+            .map(|ore| {
+                let ore = ore?;
+                ore["id"].as_i64().ok_or(Error::Json(ore))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let plan = RenamePlan {
+            from,
+            to,
+            summary,
+            sizes: sheet.sizes,
+            tiles,
+            ores,
+        };
+        let mut report = RenameReport::default();
+        match plan.apply(self, token, &mut report) {
+            Ok(()) => Ok(report),
+            Err(error) => Err(RenameError { report, error }.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lang, validate_lang, validate_sizes, validate_tiles, NewTile};
+
+    fn tile(name: &str, x: u32, y: u32) -> NewTile {
+        NewTile {
+            name: name.into(),
+            x,
+            y,
+            z: 0,
+        }
+    }
+    #[test]
+    fn lang_values_with_equals() {
+        let lang = parse_lang("tile.a.name=A = B\nitem.b.name=x=y=z");
+        assert_eq!(lang["tile.a.name"], "A = B");
+        assert_eq!(lang["item.b.name"], "x=y=z");
+    }
+    #[test]
+    fn lang_comments_and_blanks() {
+        let lang = parse_lang("# tile.a.name=A\n\n  #x=y\nnot a pair\ntile.b.name = B \n");
+        assert_eq!(lang.len(), 1);
+        assert_eq!(lang["tile.b.name"], "B");
+    }
+    #[test]
+    fn lang_codes() {
+        for lang in ["en", "fr", "zh-hans", "de-formal", "nan"] {
+            assert!(validate_lang(lang).is_ok(), "{lang}");
+        }
+        for lang in ["", "e", "EN", "en_US", "en-", "en|fr", "english"] {
+            assert!(validate_lang(lang).is_err(), "{lang}");
+        }
+    }
+    #[test]
+    fn tiles() {
+        assert!(validate_tiles(&[tile("Stone", 0, 0), tile("Dirt", 1, 0)]).is_ok());
+        assert!(validate_tiles(&[tile("A|B", 0, 0)]).is_err());
+        assert!(validate_tiles(&[tile("", 0, 0)]).is_err());
+        assert!(validate_tiles(&[tile(" Stone", 0, 0)]).is_err());
+        assert!(validate_tiles(&[tile("Sto\nne", 0, 0)]).is_err());
+        assert!(validate_tiles(&[tile("Stone", 0, 0), tile("Stone", 1, 0)]).is_err());
+        assert!(validate_tiles(&[tile("Stone", 0, 0), tile("Dirt", 0, 0)]).is_err());
+    }
+    #[test]
+    fn sizes() {
+        assert_eq!(validate_sizes(&[16, 32]).unwrap(), "16,32");
+        assert!(validate_sizes(&[]).is_err());
+        assert!(validate_sizes(&[16, 0]).is_err());
+        assert!(validate_sizes(&[16, 32, 16]).is_err());
+    }
 }
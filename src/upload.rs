@@ -0,0 +1,391 @@
+use crate::{extract, file::ImageInfo, ApiError, Csrf, Error, Json, Mediawiki, Token, Upload};
+use reqwest::blocking::multipart::{Form, Part};
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+pub struct ChunkedUpload<'a> {
+    pub chunk_size: usize,
+    pub retries: u32,
+    pub ignorewarnings: bool,
+    pub asynchronous: bool,
+    pub poll_timeout: Duration,
+    pub progress: Option<&'a mut dyn FnMut(u64, u64)>,
+}
+impl<'a> Default for ChunkedUpload<'a> {
+    fn default() -> ChunkedUpload<'a> {
+        ChunkedUpload {
+            chunk_size: 5 << 20,
+            retries: 3,
+            ignorewarnings: false,
+            asynchronous: false,
+            poll_timeout: Duration::from_secs(600),
+            progress: None,
+        }
+    }
+}
+#[derive(Debug)]
+pub enum UploadResult {
+    Success {
+        filename: String,
+        imageinfo: Option<Box<ImageInfo>>,
+    },
+    Warning {
+        filekey: String,
+        warnings: Vec<UploadWarning>,
+    },
+    Continue {
+        filekey: String,
+        offset: u64,
+    },
+    Poll {
+        filekey: String,
+        stage: String,
+    },
+}
+impl UploadResult {
+    fn parse(json: Json) -> Result<UploadResult, Error> {
+        let upload = &json["upload"];
+        let filekey = || upload_filekey(&json);
+        match upload_result(&json)? {
+            "Success" => Ok(UploadResult::Success {
+                filename: upload["filename"].as_str().unwrap_or_default().into(),
+                imageinfo: Option::deserialize(&upload["imageinfo"])
+                    .map_err(|_| Error::Json(json.clone()))?,
+            }),
+            "Warning" => {
+                let warnings = upload["warnings"]
+                    .as_object()
+                    .ok_or_else(|| Error::Json(json.clone()))?
+                    .iter()
+                    .map(|(key, val)| UploadWarning::parse(key, val))
+                    .collect();
+                Ok(UploadResult::Warning {
+                    filekey: filekey()?,
+                    warnings,
+                })
+            }
+            "Continue" => Ok(UploadResult::Continue {
+                filekey: filekey()?,
+                offset: upload["offset"].as_u64().unwrap_or_default(),
+            }),
+            "Poll" => Ok(UploadResult::Poll {
+                filekey: filekey()?,
+                stage: upload["stage"].as_str().unwrap_or_default().into(),
+            }),
+            _ => Err(Error::Json(json.clone())),
+        }
+    }
+}
+#[derive(Debug)]
+pub enum UploadWarning {
+    Exists(String),
+    ExistsNormalized(String),
+    WasDeleted(String),
+    Duplicate(Vec<String>),
+    DuplicateArchive(String),
+    BadFilename(String),
+    NoChange,
+    Other(String, Json),
+}
+impl UploadWarning {
+    fn parse(key: &str, val: &Json) -> UploadWarning {
+        let text = || val.as_str().map(String::from);
+        let warning = match key {
+            "exists" => text().map(UploadWarning::Exists),
+            "exists-normalized" => text().map(UploadWarning::ExistsNormalized),
+            "was-deleted" => text().map(UploadWarning::WasDeleted),
+            "duplicate-archive" => text().map(UploadWarning::DuplicateArchive),
+            "badfilename" => text().map(UploadWarning::BadFilename),
+            "nochange" => Some(UploadWarning::NoChange),
+            "duplicate" => val.as_array().map(|names| {
+                UploadWarning::Duplicate(
+                    names
+                        .iter()
+                        .filter_map(|name| name.as_str().map(Into::into))
+                        .collect(),
+                )
+            }),
+            _ => None,
+        };
+        warning.unwrap_or_else(|| UploadWarning::Other(key.into(), val.clone()))
+    }
+}
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UploadPolicy {
+    pub exists: bool,
+    pub exists_normalized: bool,
+    pub was_deleted: bool,
+    pub duplicate: bool,
+    pub duplicate_archive: bool,
+    pub badfilename: bool,
+    pub nochange: bool,
+}
+impl UploadPolicy {
+    pub fn allows(&self, warning: &UploadWarning) -> bool {
+        match warning {
+            UploadWarning::Exists(_) => self.exists,
+            UploadWarning::ExistsNormalized(_) => self.exists_normalized,
+            UploadWarning::WasDeleted(_) => self.was_deleted,
+            UploadWarning::Duplicate(_) => self.duplicate,
+            UploadWarning::DuplicateArchive(_) => self.duplicate_archive,
+            UploadWarning::BadFilename(_) => self.badfilename,
+            UploadWarning::NoChange => self.nochange,
+            UploadWarning::Other(..) => false,
+        }
+    }
+}
+#[derive(Debug, Deserialize)]
+pub struct FileRevertResult {
+    pub result: String,
+}
+fn upload_form(filename: &str, token: &Token<Csrf>) -> Form {
+    Form::new()
+        .text("format", "json")
+        .text("formatversion", "2")
+        .text("action", "upload")
+        .text("filename", filename.to_owned())
+        .text("token", token.value().to_owned())
+}
+fn upload_result(json: &Json) -> Result<&str, Error> {
+    json["upload"]["result"]
+        .as_str()
+        .ok_or_else(|| Error::Json(json.clone()))
+}
+fn upload_filekey(json: &Json) -> Result<String, Error> {
+    json["upload"]["filekey"]
+        .as_str()
+        .map(Into::into)
+        .ok_or_else(|| Error::Json(json.clone()))
+}
+impl Mediawiki {
+    pub fn upload(
+        &self,
+        filename: &str,
+        token: &Token<Csrf>,
+        file: Upload,
+        text: Option<&str>,
+        comment: Option<&str>,
+        ignorewarnings: bool,
+    ) -> Result<UploadResult, Error> {
+        let mut form = upload_form(filename, token);
+        if let Some(text) = text {
+            form = form.text("text", text.to_owned());
+        }
+        if let Some(comment) = comment {
+            form = form.text("comment", comment.to_owned());
+        }
+        if ignorewarnings {
+            form = form.text("ignorewarnings", "1");
+        }
+        form = match file {
+            Upload::File(file) => form.file("file", file)?,
+            Upload::Filekey(filekey) => form.text("filekey", filekey.to_owned()),
+            Upload::Url(url) => form.text("url", url.to_owned()),
+        };
+        UploadResult::parse(self.request().multipart(form)?)
+    }
+    pub fn upload_with_policy(
+        &self,
+        filename: &str,
+        token: &Token<Csrf>,
+        file: Upload,
+        text: Option<&str>,
+        comment: Option<&str>,
+        policy: &UploadPolicy,
+    ) -> Result<UploadResult, Error> {
+        let result = self.upload(filename, token, file, text, comment, false)?;
+        let UploadResult::Warning { filekey, warnings } = &result else {
+            return Ok(result);
+        };
+        if !warnings.iter().all(|warning| policy.allows(warning)) {
+            return Ok(result);
+        }
+        let filename = warnings
+            .iter()
+            .find_map(|warning| match warning {
+                UploadWarning::BadFilename(name) => Some(&**name),
+                _ => None,
+            })
+            .unwrap_or(filename);
+        self.upload(
+            filename,
+            token,
+            Upload::Filekey(filekey),
+            text,
+            comment,
+            true,
+        )
+    }
+    fn send_retrying<F>(&self, retries: u32, form: F) -> Result<Json, Error>
+    where
+        F: Fn() -> Form,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.request().multipart(form()) {
+                Ok(json) => return Ok(json),
+                Err(err @ (Error::Api(_) | Error::Json(_))) => return Err(err),
+                Err(err) if attempt < retries => {
+                    println!("{err:?}");
+                    attempt += 1;
+                    sleep(Duration::from_secs(5));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    fn poll_upload(
+        &self,
+        filename: &str,
+        token: &Token<Csrf>,
+        timeout: Duration,
+        mut json: Json,
+    ) -> Result<Json, Error> {
+        let start = Instant::now();
+        while upload_result(&json)? == "Poll" {
+            if start.elapsed() > timeout {
+                return Err(Error::Status(format!(
+                    "Timed out waiting for upload of {filename}: {json}"
+                )));
+            }
+            sleep(Duration::from_secs(2));
+            let filekey = upload_filekey(&json)?;
+            let form = upload_form(filename, token)
+                .text("checkstatus", "1")
+                .text("filekey", filekey);
+            json = self.request().multipart(form)?;
+        }
+        Ok(json)
+    }
+    pub fn upload_chunked(
+        &self,
+        filename: &str,
+        token: &Token<Csrf>,
+        path: &Path,
+        text: Option<&str>,
+        comment: Option<&str>,
+        mut options: ChunkedUpload,
+    ) -> Result<UploadResult, Error> {
+        let mut file = File::open(path)?;
+        let filesize = file.metadata()?.len();
+        if filesize == 0 {
+            return Err(Error::Invalid(format!(
+                "Cannot upload empty file {filename}"
+            )));
+        }
+        let mut offset = 0;
+        let mut filekey: Option<String> = None;
+        let mut json = Json::Null;
+        while offset < filesize {
+            let mut chunk = Vec::with_capacity(options.chunk_size);
+            (&mut file)
+                .take(options.chunk_size as u64)
+                .read_to_end(&mut chunk)?;
+            let sent = self.send_retrying(options.retries, || {
+                let mut form = upload_form(filename, token)
+                    .text("stash", "1")
+                    .text("filesize", filesize.to_string())
+                    .text("offset", offset.to_string())
+                    .part(
+                        "chunk",
+                        Part::bytes(chunk.clone()).file_name(filename.to_owned()),
+                    );
+                if let Some(filekey) = &filekey {
+                    form = form.text("filekey", filekey.clone());
+                }
+                if options.asynchronous {
+                    form = form.text("async", "1");
+                }
+                form
+            });
+            json = match sent {
+                Err(Error::Api(ApiError {
+                    offset: Some(server),
+                    ..
+                })) if filekey.is_some() && server > offset && server < filesize => {
+                    file.seek(SeekFrom::Start(server))?;
+                    offset = server;
+                    if let Some(progress) = &mut options.progress {
+                        progress(offset, filesize);
+                    }
+                    continue;
+                }
+                sent => sent?,
+            };
+            match upload_result(&json)? {
+                "Continue" | "Success" | "Poll" => (),
+                _ => return UploadResult::parse(json),
+            }
+            offset += chunk.len() as u64;
+            filekey = Some(upload_filekey(&json)?);
+            if let Some(progress) = &mut options.progress {
+                progress(offset, filesize);
+            }
+        }
+        json = self.poll_upload(filename, token, options.poll_timeout, json)?;
+        if upload_result(&json)? != "Success" {
+            return UploadResult::parse(json);
+        }
+        let mut form = upload_form(filename, token).text("filekey", upload_filekey(&json)?);
+        if let Some(text) = text {
+            form = form.text("text", text.to_owned());
+        }
+        if let Some(comment) = comment {
+            form = form.text("comment", comment.to_owned());
+        }
+        if options.ignorewarnings {
+            form = form.text("ignorewarnings", "1");
+        }
+        if options.asynchronous {
+            form = form.text("async", "1");
+        }
+        let json = self.request().multipart(form)?;
+        UploadResult::parse(self.poll_upload(filename, token, options.poll_timeout, json)?)
+    }
+    pub fn filerevert(
+        &self,
+        token: &Token<Csrf>,
+        filename: &str,
+        archivename: &str,
+        comment: Option<&str>,
+    ) -> Result<FileRevertResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "filerevert");
+        request.arg("token", token.value());
+        request.arg("filename", filename);
+        request.arg("archivename", archivename);
+        request.argo("comment", comment);
+        extract(&request.post()?, "filerevert")
+    }
+    pub fn revert_file<F>(
+        &self,
+        token: &Token<Csrf>,
+        filename: &str,
+        comment: Option<&str>,
+        good: F,
+    ) -> Result<Option<FileRevertResult>, Error>
+    where
+        F: Fn(&ImageInfo) -> bool,
+    {
+        let history = self.file_history(filename)?;
+        let Some((current, older)) = history.split_first() else {
+            return Ok(None);
+        };
+        let previous = older
+            .iter()
+            .filter(|info| info.archivename.is_some() && info.sha1 != current.sha1)
+            .find(|info| good(info));
+        match previous.and_then(|info| info.archivename.as_deref()) {
+            Some(archivename) => self
+                .filerevert(token, filename, archivename, comment)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{extract, Error, Mediawiki, QueryBuilder, Token, UserRights};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Deserialize)]
+pub struct UserRightsResult {
+    pub user: String,
+    pub userid: u64,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+#[derive(Debug, Deserialize)]
+pub struct User {
+    pub userid: Option<u64>,
+    pub name: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub rights: Vec<String>,
+    pub editcount: Option<u64>,
+    pub registration: Option<String>,
+    #[serde(default)]
+    pub missing: bool,
+    #[serde(default)]
+    pub invalid: bool,
+}
+impl Mediawiki {
+    pub fn user_rights(
+        &self,
+        token: &Token<UserRights>,
+        user: &str,
+        add: &[&str],
+        remove: &[&str],
+        expiry: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<UserRightsResult, Error> {
+        let mut request = self.request();
+        request.arg("action", "userrights");
+        request.arg("token", token.value());
+        request.arg("user", user);
+        if !add.is_empty() {
+            request.arg("add", add.join("|"));
+            request.argo("expiry", expiry);
+        }
+        if !remove.is_empty() {
+            request.arg("remove", remove.join("|"));
+        }
+        request.argo("reason", reason);
+        extract(&request.post()?, "userrights")
+    }
+    pub fn query_users(&self, users: &[&str]) -> QueryBuilder<'_, User> {
+        let mut query = self.query("users").typed();
+        query.arg("list", "users");
+        query.arg("ususers", users.join("|"));
+        query.arg("usprop", "groups|rights|editcount|registration");
+        query
+    }
+    pub fn query_allusers(&self, groups: &[&str]) -> QueryBuilder<'_, User> {
+        let mut query = self.query("allusers").typed();
+        query.arg("list", "allusers");
+        query.arg("aulimit", "max");
+        query.arg("auprop", "groups|rights|editcount|registration");
+        if !groups.is_empty() {
+            query.arg("augroup", groups.join("|"));
+        }
+        query
+    }
+    pub fn ensure_groups(
+        &self,
+        token: &Token<UserRights>,
+        roster: &BTreeMap<String, BTreeSet<String>>,
+        managed: &[&str],
+        reason: Option<&str>,
+    ) -> Result<Vec<UserRightsResult>, Error> {
+        if managed.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some((user, group)) = roster
+            .iter()
+            .flat_map(|(user, groups)| groups.iter().map(move |group| (user, group)))
+            .find(|(_, group)| !managed.contains(&group.as_str()))
+        {
+            return Err(Error::Invalid(format!(
+                "Roster gives {user} unmanaged group {group}"
+            )));
+        }
+        let mut current = BTreeMap::new();
+        for user in self.query_allusers(managed) {
+            let user = user?;
+            let groups = user
+                .groups
+                .into_iter()
+                .filter(|group| managed.contains(&group.as_str()))
+                .collect::<BTreeSet<_>>();
+            current.insert(user.name, groups);
+        }
+        let empty = BTreeSet::new();
+        let mut users = roster
+            .keys()
+            .map(|user| user.replace('_', " "))
+            .collect::<BTreeSet<_>>();
+        users.extend(current.keys().cloned());
+        let mut results = Vec::new();
+        for user in users {
+            let want = roster
+                .get(&user)
+                .or_else(|| roster.get(&user.replace(' ', "_")))
+                .unwrap_or(&empty);
+            let have = current.get(&user).unwrap_or(&empty);
+            let add = want.difference(have).map(|g| &**g).collect::<Vec<_>>();
+            let remove = have.difference(want).map(|g| &**g).collect::<Vec<_>>();
+            if add.is_empty() && remove.is_empty() {
+                continue;
+            }
+            results.push(self.user_rights(token, &user, &add, &remove, None, reason)?);
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{extract, Change, Error, Mediawiki, QueryBuilder, Title, Token, Watch};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct WatchResult {
+    pub ns: Option<i32>,
+    pub title: String,
+    #[serde(default)]
+    pub watched: bool,
+    #[serde(default)]
+    pub unwatched: bool,
+    #[serde(default)]
+    pub missing: bool,
+    pub expiry: Option<String>,
+}
+impl Mediawiki {
+    fn watch_titles(
+        &self,
+        token: &Token<Watch>,
+        titles: &[&str],
+        unwatch: bool,
+        expiry: Option<&str>,
+    ) -> Result<Vec<WatchResult>, Error> {
+        let mut results = Vec::new();
+        for chunk in titles.chunks(50) {
+            let mut request = self.request();
+            request.arg("action", "watch");
+            request.arg("token", token.value());
+            request.arg("titles", chunk.join("|"));
+            request.flag("unwatch", unwatch);
+            request.argo("expiry", expiry);
+            let mut chunk: Vec<WatchResult> = extract(&request.post()?, "watch")?;
+            results.append(&mut chunk);
+        }
+        Ok(results)
+    }
+    pub fn watch(
+        &self,
+        token: &Token<Watch>,
+        titles: &[&str],
+        expiry: Option<&str>,
+    ) -> Result<Vec<WatchResult>, Error> {
+        self.watch_titles(token, titles, false, expiry)
+    }
+    pub fn unwatch(
+        &self,
+        token: &Token<Watch>,
+        titles: &[&str],
+    ) -> Result<Vec<WatchResult>, Error> {
+        self.watch_titles(token, titles, true, None)
+    }
+    pub fn query_watchlistraw(&self) -> QueryBuilder<'_, Title> {
+        let mut query = self.query("watchlistraw").typed();
+        query.arg("list", "watchlistraw");
+        query.arg("wrlimit", "max");
+        query
+    }
+    pub fn query_watchlist(&self) -> QueryBuilder<'_, Change> {
+        let mut query = self.query("watchlist").typed();
+        query.arg("list", "watchlist");
+        query.arg("wldir", "older");
+        query.arg(
+            "wlprop",
+            "ids|title|flags|user|userid|comment|timestamp|sizes|loginfo|tags",
+        );
+        query.arg("wllimit", "max");
+        query
+    }
+}